@@ -1,5 +1,5 @@
 use chromiumoxide::cdp::browser_protocol::network::CookieParam;
-use kagisearch::{AuthType, Kagi};
+use kagisearch::{AuthType, Kagi, SearchOptions, TwoFactorCode};
 use tokio::io::AsyncBufReadExt;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::{EnvFilter, fmt};
@@ -40,18 +40,18 @@ async fn main() -> anyhow::Result<()> {
                 let mut password = String::new();
                 println!("Please input your Password:");
                 reader.read_line(&mut password).await?;
-                let mut code = String::new();
-                println!("Please input your Two-factor Authentication code (if enabled):");
-                reader.read_line(&mut code).await?;
-                let code = code.trim();
+                // Only asked for if Kagi requires it
+                let code = TwoFactorCode::provider(|| async {
+                    let mut code = String::new();
+                    println!("Please input your Two-factor Authentication code:");
+                    let mut reader = tokio::io::BufReader::new(tokio::io::stdin());
+                    reader.read_line(&mut code).await.ok()?;
+                    Some(code.trim().to_string()).filter(|code| !code.is_empty())
+                });
                 AuthType::Login(
                     email.trim().to_string(),
                     password.trim().to_string(),
-                    if code.is_empty() {
-                        None
-                    } else {
-                        Some(code.to_string())
-                    },
+                    Some(code),
                 )
             }
             _ => {
@@ -68,7 +68,14 @@ async fn main() -> anyhow::Result<()> {
     } else {
         (Kagi::new(AuthType::Icognito).await?, Some(auth_type))
     };
-    let result = kagi.search("What is Kagi Search", 5, auth_type).await?;
+    let result = kagi
+        .search(
+            "What is Kagi Search",
+            5,
+            &SearchOptions::default(),
+            auth_type,
+        )
+        .await?;
     let Some(result) = result else {
         return Err(anyhow::anyhow!("No result found"));
     };
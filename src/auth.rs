@@ -1,75 +1,205 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
 use chromiumoxide::Page;
+use futures::future::BoxFuture;
+use totp_rs::{Algorithm, Secret, TOTP};
 use url::Url;
+use zeroize::Zeroizing;
+
+use crate::{Error, Phase, error::is_missing, timeout::timeout};
+
+const SIGNIN_FORM_SELECTOR: &str = "#signInForm";
+const EMAIL_INPUT_SELECTOR: &str = "input[name='email']";
+const PASSWORD_INPUT_SELECTOR: &str = "input[name='password']";
+const CODE_INPUT_SELECTOR: &str = "input[name='code']";
+const SUBMIT_SELECTOR: &str = "button[type='submit']";
+
+/// Supplies the two-factor authentication code once Kagi asks for it, e.g. by prompting the user.
+///
+/// Implemented for async closures returning `Option<String>`. Time spent waiting for the code
+/// does not count towards [`Timeouts::sign_in`](crate::Timeouts::sign_in).
+pub trait TwoFactorProvider: Send + Sync {
+    /// Code to submit, `None` to give up with [`Error::TwoFactorRequired`]
+    fn code(&self) -> BoxFuture<'_, Option<String>>;
+}
+
+impl<F, Fut> TwoFactorProvider for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Option<String>> + Send + 'static,
+{
+    fn code(&self) -> BoxFuture<'_, Option<String>> {
+        Box::pin(self())
+    }
+}
+
+/// Two-factor authentication code of [`AuthType::Login`](crate::AuthType::Login)
+#[derive(Clone)]
+pub enum TwoFactorCode {
+    /// Code known upfront
+    Fixed(String),
+    /// Code asked for only if Kagi requires one
+    Provider(Arc<dyn TwoFactorProvider>),
+}
 
-use crate::{Error, auth_error, browser_error, consts::HOST};
+impl TwoFactorCode {
+    /// Ask `provider` for the code once Kagi requires one
+    pub fn provider(provider: impl TwoFactorProvider + 'static) -> Self {
+        Self::Provider(Arc::new(provider))
+    }
+}
+
+impl From<String> for TwoFactorCode {
+    fn from(code: String) -> Self {
+        Self::Fixed(code)
+    }
+}
+
+impl From<&str> for TwoFactorCode {
+    fn from(code: &str) -> Self {
+        Self::Fixed(code.to_string())
+    }
+}
+
+/// Source of the two-factor authentication code
+pub(crate) enum TwoFactor<'a> {
+    /// Code supplied upfront or by a provider, if any
+    Code(Option<&'a TwoFactorCode>),
+    /// Base32 TOTP secret generating the current code
+    Totp(&'a str),
+}
+
+impl TwoFactor<'_> {
+    /// Code to submit, `None` if none was supplied
+    async fn code(&self) -> Result<Option<String>, Error> {
+        match self {
+            TwoFactor::Code(None) => Ok(None),
+            TwoFactor::Code(Some(TwoFactorCode::Fixed(code))) => Ok(Some(code.clone())),
+            TwoFactor::Code(Some(TwoFactorCode::Provider(provider))) => Ok(provider.code().await),
+            TwoFactor::Totp(secret) => totp_code(secret)
+                .map(Some)
+                .map_err(Error::InvalidTotpSecret),
+        }
+    }
+}
+
+/// Current code of the TOTP with the base32 `secret`, using the common SHA-1, 6 digits and
+/// 30 seconds parameters
+fn totp_code(secret: &str) -> Result<String, String> {
+    let secret: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .collect();
+    let secret = Secret::Encoded(secret.to_uppercase())
+        .to_bytes()
+        .map_err(|e| e.to_string())?;
+    if secret.is_empty() {
+        return Err("empty secret".to_string());
+    }
+    TOTP::new_unchecked(Algorithm::SHA1, 6, 1, 30, secret)
+        .generate_current()
+        .map_err(|e| e.to_string())
+}
 
 /// Handle token authentication
-pub(crate) async fn handle_token(page: &Page, token: &str) -> Result<(), Error> {
-    let url = Url::parse_with_params(&format!("{}/search", HOST), &[("token", token)])?;
-    page.goto(url).await?.wait_for_navigation().await?;
-    let Some(url) = page.url().await? else {
-        return Err(browser_error!("Failed to get URL"));
-    };
+pub(crate) async fn handle_token(page: &Page, host: &Url, token: &str) -> Result<(), Error> {
+    let mut url = host.join("search")?;
+    url.query_pairs_mut().append_pair("token", token);
+    // The URL carries the token, wipe it along with the token itself
+    let url = Zeroizing::new(String::from(url));
+    page.goto(url.as_str()).await?.wait_for_navigation().await?;
     // Verify token
-    if Url::parse(&url)?.path() != "/" {
-        return Err(auth_error!("Invalid token"));
+    if current_url(page, host, "search").await?.path() != host.path() {
+        return Err(Error::InvalidToken);
     }
     Ok(())
 }
 
-/// Handle login authentication
+/// Handle login authentication, bounding each submission of the form by `limit`
 pub(crate) async fn handle_signin(
     page: &Page,
+    host: &Url,
     email: &str,
     password: &str,
-    code: Option<&str>,
+    two_factor: TwoFactor<'_>,
+    limit: Duration,
 ) -> Result<(), Error> {
-    // Fill in the login form
-    let form = page.find_element("#signInForm").await?;
+    let signin = host.join("signin")?;
+    let search = host.join("search")?;
+    let mut url = timeout(Phase::SignIn, limit, async {
+        // Fill in the login form
+        let form = find(page, SIGNIN_FORM_SELECTOR).await?;
 
-    // Handle email input
-    let input = form.find_element("input[name='email']").await?;
-    input.click().await?.type_str(email).await?;
+        // Handle email input
+        let input = find_in(&form, EMAIL_INPUT_SELECTOR).await?;
+        input.click().await?.type_str(email).await?;
 
-    // Handle password input
-    let input = form.find_element("input[name='password']").await?;
-    input.click().await?.type_str(password).await?;
+        // Handle password input
+        let input = find_in(&form, PASSWORD_INPUT_SELECTOR).await?;
+        input.click().await?.type_str(password).await?;
 
-    // submit the form
-    form.find_element("button[type='submit']")
-        .await?
-        .click()
-        .await?;
-    page.wait_for_navigation().await?;
-
-    let Some(url) = page.url().await? else {
-        return Err(browser_error!("Failed to get URL"));
-    };
-
-    let mut url = Url::parse(&url)?;
-    // Handle 2FA
-    if url.path() == "/signin" {
-        let Some(code) = code else {
-            return Err(auth_error!("2FA code required"));
-        };
-        let form = page.find_element("#signInForm").await?;
-        let input = form.find_element("input[name='code']").await?;
-        input.click().await?.type_str(code).await?;
-        form.find_element("button[type='submit']")
-            .await?
-            .click()
-            .await?;
+        // submit the form
+        find_in(&form, SUBMIT_SELECTOR).await?.click().await?;
         page.wait_for_navigation().await?;
+        current_url(page, host, "signin").await
+    })
+    .await?;
 
-        let Some(new_url) = page.url().await? else {
-            return Err(browser_error!("Failed to get URL"));
+    // Handle 2FA
+    if url.path() == signin.path() {
+        // Still on the sign in page without a code field means the credentials were wrong
+        match page.find_element(CODE_INPUT_SELECTOR).await {
+            Ok(_) => {}
+            Err(e) if is_missing(&e) => return Err(Error::LoginRejected),
+            Err(e) => return Err(e.into()),
+        }
+        // Not bounded by the timeout, the provider may be waiting for the user
+        let Some(code) = two_factor.code().await? else {
+            return Err(Error::TwoFactorRequired);
         };
-        url = Url::parse(&new_url)?;
+        url = timeout(Phase::SignIn, limit, async {
+            let form = find(page, SIGNIN_FORM_SELECTOR).await?;
+            let input = find_in(&form, CODE_INPUT_SELECTOR).await?;
+            input.click().await?.type_str(&code).await?;
+            find_in(&form, SUBMIT_SELECTOR).await?.click().await?;
+            page.wait_for_navigation().await?;
+            current_url(page, host, "signin").await
+        })
+        .await?;
+        if url.path() == signin.path() {
+            return Err(Error::InvalidTwoFactorCode);
+        }
     }
 
-    if url.path() != "/search" {
-        return Err(auth_error!("Login failed"));
+    if url.path() != search.path() {
+        return Err(Error::LoginRejected);
     }
 
     Ok(())
 }
+
+/// URL of the page after navigating to `path` relative to `host`
+async fn current_url(page: &Page, host: &Url, path: &str) -> Result<Url, Error> {
+    let Some(url) = page.url().await? else {
+        return Err(Error::NavigationFailed {
+            url: host.join(path)?.to_string(),
+        });
+    };
+    Ok(Url::parse(&url)?)
+}
+
+async fn find(page: &Page, selector: &str) -> Result<chromiumoxide::Element, Error> {
+    page.find_element(selector)
+        .await
+        .map_err(|e| Error::lookup(selector, e))
+}
+
+async fn find_in(
+    element: &chromiumoxide::Element,
+    selector: &str,
+) -> Result<chromiumoxide::Element, Error> {
+    element
+        .find_element(selector)
+        .await
+        .map_err(|e| Error::lookup(selector, e))
+}
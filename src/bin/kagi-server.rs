@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use kagisearch::{AuthType, Kagi, server};
+use tracing::{info, level_filters::LevelFilter};
+use tracing_subscriber::{EnvFilter, fmt};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// Serve the Kagi Search API on `KAGI_SERVER_ADDR`, signing in with `KAGI_TOKEN`.
+///
+/// Set `KAGI_API_KEY` to require clients to send `Authorization: Bot <key>`, and
+/// `KAGI_POOL_SIZE` to bound the number of concurrent searches. `KAGI_HOST` replaces the Kagi
+/// host searches are sent to.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env_lossy(),
+        )
+        .init();
+
+    let token = std::env::var("KAGI_TOKEN").map_err(|_| "KAGI_TOKEN is not set")?;
+    let api_key = std::env::var("KAGI_API_KEY").ok();
+    let addr = std::env::var("KAGI_SERVER_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+
+    let pool_size = match std::env::var("KAGI_POOL_SIZE") {
+        Ok(size) => Some(size.parse().map_err(|_| "KAGI_POOL_SIZE is not a number")?),
+        Err(_) => None,
+    };
+
+    let mut builder = Kagi::builder().auth_type(AuthType::Token(token));
+    if let Some(pool_size) = pool_size {
+        builder = builder.pool_size(pool_size);
+    }
+    if let Ok(host) = std::env::var("KAGI_HOST") {
+        builder = builder.host(host);
+    }
+    let kagi = Arc::new(builder.build().await?);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    info!("Listening on {}", addr);
+    axum::serve(listener, server::router(kagi.clone(), api_key))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    kagi.close().await?;
+    Ok(())
+}
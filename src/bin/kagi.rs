@@ -0,0 +1,471 @@
+use std::{
+    error::Error,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chromiumoxide::cdp::browser_protocol::network::CookieParam;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use kagisearch::{
+    AuthType, CommandCredential, Kagi, Lens, Proxy, SafeSearch, SearchOptions, SearchResult,
+    SortOrder, Summary, SummaryType, TimeRange, Timeouts, TwoFactorCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, level_filters::LevelFilter};
+use tracing_subscriber::{EnvFilter, fmt};
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+/// Search Kagi from the command line without consuming API credits
+#[derive(Parser)]
+#[command(name = "kagi", version, about)]
+struct Cli {
+    /// Path of the config file, defaults to `<config dir>/kagi/config.toml`
+    #[arg(long, global = true, env = "KAGI_CONFIG")]
+    config: Option<PathBuf>,
+    /// Path of the saved session cookies, defaults to `<data dir>/kagi/cookies.json`
+    #[arg(long, global = true, env = "KAGI_COOKIES")]
+    cookies: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search Kagi
+    Search(SearchArgs),
+    /// Sign in and save the session cookies
+    Login {
+        /// Sign in with email and password instead of a token
+        #[arg(long)]
+        email: bool,
+    },
+    /// Export or import the saved session cookies
+    #[command(subcommand)]
+    Cookies(CookiesCommand),
+    /// Summarize a URL or text with the Universal Summarizer
+    Summarize(SummarizeArgs),
+}
+
+#[derive(Subcommand)]
+enum CookiesCommand {
+    /// Print the saved cookies, or write them to a file
+    Export {
+        /// File to write the cookies to instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Replace the saved cookies with the ones from a file
+    Import {
+        /// JSON file with the cookies, as written by `cookies export`
+        path: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Search query
+    query: String,
+    /// Maximum number of results
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Only return results from this time range
+    #[arg(long, value_enum)]
+    time: Option<TimeArg>,
+    /// Region code, e.g. `us` or `no_region`
+    #[arg(long)]
+    region: Option<String>,
+    /// Safe search mode
+    #[arg(long, value_enum)]
+    safe: Option<SafeArg>,
+    /// Sort order
+    #[arg(long, value_enum)]
+    order: Option<OrderArg>,
+    /// Match the query literally
+    #[arg(long)]
+    verbatim: bool,
+    /// Results page to start from
+    #[arg(long)]
+    page: Option<u32>,
+    /// Lens name (forums, programming, world-news, academic, pdfs, non-commercial, small-web)
+    /// or custom lens id
+    #[arg(long)]
+    lens: Option<String>,
+    /// Proxy URL for this search, e.g. `socks5://127.0.0.1:1080`
+    #[arg(long)]
+    proxy: Option<Proxy>,
+    /// Give up on the search after this many seconds
+    #[arg(long)]
+    timeout: Option<u64>,
+}
+
+#[derive(Args)]
+struct SummarizeArgs {
+    /// URL or text to summarize, `-` reads text from stdin
+    input: String,
+    /// Produce a list of key moments instead of a summary
+    #[arg(long)]
+    key_moments: bool,
+    /// Language code of the summary, e.g. `EN`
+    #[arg(long)]
+    language: Option<String>,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Jsonl,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TimeArg {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SafeArg {
+    On,
+    Off,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OrderArg {
+    Default,
+    Recency,
+    Website,
+    Trackers,
+}
+
+/// Contents of the config file
+#[derive(Default, Deserialize)]
+struct Config {
+    token: Option<String>,
+    email: Option<String>,
+    password: Option<String>,
+    /// Command printing the token, such as `["pass", "show", "kagi/token"]`
+    token_command: Option<Vec<String>>,
+    /// Command printing the password
+    password_command: Option<Vec<String>>,
+    /// Base32 TOTP secret generating the two-factor codes
+    totp_secret: Option<String>,
+    cookies: Option<PathBuf>,
+    host: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> CliResult<()> {
+    fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::WARN.into())
+                .from_env_lossy(),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    let config = load_config(cli.config.as_deref())?;
+    let cookie_path = match cli.cookies.or_else(|| config.cookies.clone()) {
+        Some(path) => path,
+        None => dirs::data_dir()
+            .ok_or("No data directory found, pass --cookies")?
+            .join("kagi")
+            .join("cookies.json"),
+    };
+
+    match cli.command {
+        Command::Search(args) => search(&config, &cookie_path, args).await,
+        Command::Login { email } => login(&config, &cookie_path, email).await,
+        Command::Cookies(CookiesCommand::Export { output }) => {
+            let content = std::fs::read_to_string(&cookie_path)
+                .map_err(|e| format!("No saved cookies at {}: {}", cookie_path.display(), e))?;
+            match output {
+                Some(output) => save_cookies(&output, &content)?,
+                None => println!("{}", content),
+            }
+            Ok(())
+        }
+        Command::Cookies(CookiesCommand::Import { path }) => {
+            let cookies = read_cookies(&path)?;
+            save_cookies(&cookie_path, &serde_json::to_string(&cookies)?)
+        }
+        Command::Summarize(args) => summarize(&config, &cookie_path, args).await,
+    }
+}
+
+async fn search(config: &Config, cookie_path: &Path, args: SearchArgs) -> CliResult<()> {
+    let options = SearchOptions {
+        time_range: args.time.map(|time| match time {
+            TimeArg::Day => TimeRange::Day,
+            TimeArg::Week => TimeRange::Week,
+            TimeArg::Month => TimeRange::Month,
+            TimeArg::Year => TimeRange::Year,
+        }),
+        region: args.region,
+        safe_search: args.safe.map(|safe| match safe {
+            SafeArg::On => SafeSearch::On,
+            SafeArg::Off => SafeSearch::Off,
+        }),
+        order: args.order.map(|order| match order {
+            OrderArg::Default => SortOrder::Default,
+            OrderArg::Recency => SortOrder::Recency,
+            OrderArg::Website => SortOrder::Website,
+            OrderArg::Trackers => SortOrder::Trackers,
+        }),
+        verbatim: args.verbatim,
+        page: args.page,
+        lens: args.lens.as_deref().map(parse_lens),
+        proxy: args.proxy,
+        timeouts: args.timeout.map(|timeout| Timeouts {
+            overall: Some(Duration::from_secs(timeout)),
+            ..Default::default()
+        }),
+    };
+
+    let kagi = open_session(config, cookie_path).await?;
+    let results = kagi.search(&args.query, args.limit, &options, None).await;
+    let saved = save_session(&kagi, cookie_path).await;
+    kagi.close().await?;
+    let results = results?.unwrap_or_default();
+    saved?;
+
+    match args.format {
+        Format::Table => print_table(&results),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        Format::Jsonl => print_jsonl(&results)?,
+    }
+    Ok(())
+}
+
+async fn login(config: &Config, cookie_path: &Path, email: bool) -> CliResult<()> {
+    let auth_type = if email {
+        let email = match &config.email {
+            Some(email) => email.clone(),
+            None => prompt("Email or Username: ")?,
+        };
+        let password = match &config.password {
+            Some(password) => password.clone(),
+            None => rpassword::prompt_password("Password: ")?,
+        };
+        match &config.totp_secret {
+            Some(secret) => AuthType::LoginTotp(email, password, secret.clone()),
+            None => {
+                let code = TwoFactorCode::provider(|| async {
+                    // Only prompted if Kagi asks for a code
+                    tokio::task::spawn_blocking(|| prompt("Two-factor Authentication code: ").ok())
+                        .await
+                        .ok()
+                        .flatten()
+                        .filter(|code| !code.is_empty())
+                });
+                AuthType::Login(email, password, Some(code))
+            }
+        }
+    } else {
+        let token = match &config.token {
+            Some(token) => token.clone(),
+            None => rpassword::prompt_password("Token: ")?,
+        };
+        AuthType::Token(token)
+    };
+
+    let kagi = launch(config, auth_type).await?;
+    let signed_in = kagi.sign_in(None).await;
+    let saved = match signed_in {
+        Ok(()) => save_session(&kagi, cookie_path).await,
+        Err(e) => Err(e.into()),
+    };
+    kagi.close().await?;
+    saved?;
+    eprintln!("Session saved to {}", cookie_path.display());
+    Ok(())
+}
+
+async fn summarize(config: &Config, cookie_path: &Path, args: SummarizeArgs) -> CliResult<()> {
+    let input = if args.input == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        args.input
+    };
+    let summary_type = if args.key_moments {
+        SummaryType::KeyMoments
+    } else {
+        SummaryType::Summary
+    };
+
+    let kagi = open_session(config, cookie_path).await?;
+    let summary = kagi
+        .summarize(&input, summary_type, args.language.as_deref(), None)
+        .await;
+    let saved = save_session(&kagi, cookie_path).await;
+    kagi.close().await?;
+    let summary = summary?;
+    saved?;
+
+    match (args.format, &summary) {
+        (Format::Table, Summary::Text(text)) => println!("{}", text),
+        (Format::Table, Summary::KeyMoments(moments)) => {
+            for moment in moments {
+                println!("- {}", moment);
+            }
+        }
+        (Format::Json, _) => println!("{}", serde_json::to_string_pretty(&summary)?),
+        (Format::Jsonl, _) => println!("{}", serde_json::to_string(&summary)?),
+    }
+    Ok(())
+}
+
+/// Start a browser from the saved cookies, or from the credentials in the environment or config
+async fn open_session(config: &Config, cookie_path: &Path) -> CliResult<Kagi> {
+    let auth_type = if cookie_path.exists() {
+        debug!("Loading cookies from {}", cookie_path.display());
+        AuthType::Cookies(read_cookies(cookie_path)?)
+    } else if let Some(token) = &config.token {
+        AuthType::Token(token.clone())
+    } else if let (Some(email), Some(password)) = (&config.email, &config.password) {
+        match &config.totp_secret {
+            Some(secret) => AuthType::LoginTotp(email.clone(), password.clone(), secret.clone()),
+            None => {
+                let code = std::env::var("KAGI_2FA_CODE").ok();
+                AuthType::Login(email.clone(), password.clone(), code.map(Into::into))
+            }
+        }
+    } else if let Some(command) = config.token_command.as_deref().and_then(credential_command) {
+        AuthType::TokenFrom(Arc::new(command))
+    } else if let (Some(email), Some(command)) = (
+        &config.email,
+        config
+            .password_command
+            .as_deref()
+            .and_then(credential_command),
+    ) {
+        let code = std::env::var("KAGI_2FA_CODE").ok();
+        AuthType::LoginFrom(email.clone(), Arc::new(command), code.map(Into::into))
+    } else {
+        return Err("Not signed in, run `kagi login` or set KAGI_TOKEN".into());
+    };
+    launch(config, auth_type).await
+}
+
+/// Command of a `*_command` config key, run only if Kagi asks to sign in
+fn credential_command(command: &[String]) -> Option<CommandCredential> {
+    let (program, args) = command.split_first()?;
+    Some(CommandCredential::new(program).args(args))
+}
+
+/// Start a browser signing in with `auth_type` on the configured host
+async fn launch(config: &Config, auth_type: AuthType) -> CliResult<Kagi> {
+    let mut builder = Kagi::builder().auth_type(auth_type);
+    if let Some(host) = &config.host {
+        builder = builder.host(host);
+    }
+    Ok(builder.build().await?)
+}
+
+/// Persist the cookies of the current session for the next run
+async fn save_session(kagi: &Kagi, cookie_path: &Path) -> CliResult<()> {
+    let cookies = kagi.cookies().await?;
+    save_cookies(cookie_path, &serde_json::to_string(&cookies)?)
+}
+
+fn read_cookies(path: &Path) -> CliResult<Vec<CookieParam>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Write the cookies readable by the current user only, since they sign in to the account
+fn save_cookies(path: &Path, content: &str) -> CliResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Read the config file, with `KAGI_*` environment variables taking precedence
+fn load_config(path: Option<&Path>) -> CliResult<Config> {
+    let path = match path {
+        Some(path) => Some(path.to_path_buf()),
+        None => dirs::config_dir().map(|dir| dir.join("kagi").join("config.toml")),
+    };
+    let mut config = match path {
+        Some(path) if path.exists() => toml::from_str(&std::fs::read_to_string(path)?)?,
+        _ => Config::default(),
+    };
+    if let Ok(token) = std::env::var("KAGI_TOKEN") {
+        config.token = Some(token);
+    }
+    if let Ok(email) = std::env::var("KAGI_EMAIL") {
+        config.email = Some(email);
+    }
+    if let Ok(password) = std::env::var("KAGI_PASSWORD") {
+        config.password = Some(password);
+    }
+    if let Ok(secret) = std::env::var("KAGI_TOTP_SECRET") {
+        config.totp_secret = Some(secret);
+    }
+    if let Ok(host) = std::env::var("KAGI_HOST") {
+        config.host = Some(host);
+    }
+    Ok(config)
+}
+
+fn parse_lens(lens: &str) -> Lens {
+    match lens {
+        "forums" => Lens::Forums,
+        "programming" => Lens::Programming,
+        "world-news" => Lens::WorldNews,
+        "academic" => Lens::Academic,
+        "pdfs" => Lens::Pdfs,
+        "non-commercial" => Lens::NonCommercial,
+        "small-web" => Lens::SmallWeb,
+        id => Lens::Custom(id.to_string()),
+    }
+}
+
+fn prompt(message: &str) -> CliResult<String> {
+    eprint!("{}", message);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn print_table(results: &[SearchResult]) {
+    for result in results {
+        println!("{:>3}. {}", result.rank, result.title);
+        println!("     {}", result.url);
+        if !result.snippet.is_empty() {
+            println!("     {}", result.snippet.replace('\n', " "));
+        }
+        println!();
+    }
+}
+
+fn print_jsonl<T: Serialize>(items: &[T]) -> CliResult<()> {
+    let mut stdout = std::io::stdout().lock();
+    for item in items {
+        writeln!(stdout, "{}", serde_json::to_string(item)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chromiumoxide::{BrowserConfig, handler::viewport::Viewport};
+use url::Url;
+
+use crate::{
+    AuthType, Error, Kagi, Proxy, Timeouts,
+    consts::{DEFAULT_ARGS, DEFAULT_HOST},
+};
+
+/// Builder for [`Kagi`], configuring how the browser is launched.
+///
+/// Every setting falls back to the defaults used by [`Kagi::new`]: a headless browser with a
+/// 1920x1080 viewport, launched in incognito mode with arguments tuned for scraping.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use kagisearch::{AuthType, Kagi};
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let kagi = Kagi::builder()
+///         .auth_type(AuthType::Token(std::env::var("KAGI_TOKEN")?))
+///         .chrome_executable("/usr/bin/chromium")
+///         .viewport(1280, 720)
+///         .language("en-US")
+///         .build()
+///         .await?;
+///     kagi.close().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct KagiBuilder {
+    pub(crate) auth_type: AuthType,
+    executable: Option<PathBuf>,
+    args: Vec<String>,
+    viewport: (u32, u32),
+    headful: bool,
+    window_size: Option<(u32, u32)>,
+    user_agent: Option<String>,
+    language: Option<String>,
+    launch_timeout: Option<Duration>,
+    pub(crate) user_data_dir: Option<PathBuf>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) pool_size: Option<usize>,
+    pub(crate) timeouts: Timeouts,
+    pub(crate) host: String,
+}
+
+impl Default for KagiBuilder {
+    fn default() -> Self {
+        Self {
+            auth_type: AuthType::Icognito,
+            executable: None,
+            args: DEFAULT_ARGS.iter().map(|arg| arg.to_string()).collect(),
+            viewport: (1920, 1080),
+            headful: false,
+            window_size: None,
+            user_agent: None,
+            language: None,
+            launch_timeout: None,
+            user_data_dir: None,
+            proxy: None,
+            pool_size: None,
+            timeouts: Timeouts::default(),
+            host: DEFAULT_HOST.to_string(),
+        }
+    }
+}
+
+impl KagiBuilder {
+    /// Authentication type of the instance, defaults to `AuthType::Icognito`
+    pub fn auth_type(mut self, auth_type: AuthType) -> Self {
+        self.auth_type = auth_type;
+        self
+    }
+
+    /// Path of the Chrome or Chromium executable, detected automatically by default
+    pub fn chrome_executable(mut self, path: impl AsRef<Path>) -> Self {
+        self.executable = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Add a command line argument passed to the browser
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add command line arguments passed to the browser
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Remove a command line argument, including one of the defaults
+    pub fn remove_arg(mut self, arg: &str) -> Self {
+        self.args.retain(|existing| existing != arg);
+        self
+    }
+
+    /// Size of the page viewport, defaults to 1920x1080
+    pub fn viewport(mut self, width: u32, height: u32) -> Self {
+        self.viewport = (width, height);
+        self
+    }
+
+    /// Launch the browser with a visible window instead of headless
+    pub fn headful(mut self) -> Self {
+        self.headful = true;
+        self
+    }
+
+    /// Size of the browser window
+    pub fn window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some((width, height));
+        self
+    }
+
+    /// User agent reported by the browser
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Browser language, e.g. `en-US`, also sent as `Accept-Language`
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Maximum time to wait for the browser to start
+    pub fn launch_timeout(mut self, timeout: Duration) -> Self {
+        self.launch_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep the browser state in a persistent profile directory, see [`Kagi::with_profile`]
+    pub fn user_data_dir(mut self, user_data_dir: impl AsRef<Path>) -> Self {
+        self.user_data_dir = Some(user_data_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Route all traffic through a proxy, see [`SearchOptions::proxy`](crate::SearchOptions::proxy)
+    /// to override it per search
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Keep `size` tabs open and share them between concurrent requests.
+    ///
+    /// The tabs are created and signed in when the instance is built, and each request waits for
+    /// an idle tab, so at most `size` pages are open at once. Requests that need their own browser
+    /// context, i.e. in incognito mode or with a proxy override, get a fresh page but still count
+    /// towards the limit. Without a pool, every request opens a new page.
+    pub fn pool_size(mut self, size: usize) -> Self {
+        self.pool_size = Some(size.max(1));
+        self
+    }
+
+    /// Time limits of navigation, sign in and result loading, see
+    /// [`SearchOptions::timeouts`](crate::SearchOptions::timeouts) to override them per search
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Base URL of Kagi, defaults to `https://kagi.com`
+    ///
+    /// Useful to run against a mirror, a staging host or a local stand-in, e.g. in tests. The URL
+    /// may include a path prefix, and is validated when the instance is created.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Launch the browser and create the instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The profile directory is used by another process
+    /// - Browser initialization fails
+    /// - Cookie loading fails (when using `AuthType::Cookies`)
+    /// - Signing in the page pool fails
+    /// - The host is not a valid `http(s)` URL
+    ///
+    pub async fn build(self) -> Result<Kagi, Error> {
+        Kagi::launch(self).await
+    }
+
+    /// Attach to an already running browser instead of launching one, see [`Kagi::connect`]
+    ///
+    /// Only the authentication type, proxy credentials, page pool, timeouts and host apply, the
+    /// launch settings are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Connecting to the browser fails
+    /// - Cookie loading fails (when using `AuthType::Cookies`)
+    /// - The host is not a valid `http(s)` URL
+    ///
+    pub async fn connect(self, ws_url: impl Into<String>) -> Result<Kagi, Error> {
+        Kagi::connect_with(self, ws_url.into()).await
+    }
+
+    /// Validated base URL of Kagi, ending with `/` so paths can be joined onto it
+    pub(crate) fn base_url(&self) -> Result<Url, String> {
+        let mut url = Url::parse(&self.host).map_err(|e| format!("{}: {}", self.host, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("{}: scheme must be http or https", self.host));
+        }
+        if url.host_str().is_none() {
+            return Err(format!("{}: missing host", self.host));
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err(format!("{}: query and fragment are not allowed", self.host));
+        }
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+        Ok(url)
+    }
+
+    /// Chromium launch configuration
+    pub(crate) fn browser_config(&self) -> Result<BrowserConfig, String> {
+        let (width, height) = self.viewport;
+        let viewport = Viewport {
+            width,
+            height,
+            ..Default::default()
+        };
+        let mut config = BrowserConfig::builder().viewport(viewport);
+        config = match &self.user_data_dir {
+            Some(user_data_dir) => config.user_data_dir(user_data_dir),
+            None => config.incognito(),
+        };
+        if let Some(executable) = &self.executable {
+            config = config.chrome_executable(executable);
+        }
+        if self.headful {
+            config = config.with_head();
+        }
+        if let Some((width, height)) = self.window_size {
+            config = config.window_size(width, height);
+        }
+        if let Some(launch_timeout) = self.launch_timeout {
+            config = config.launch_timeout(launch_timeout);
+        }
+        let mut args = self.args.clone();
+        if let Some(user_agent) = &self.user_agent {
+            args.push(format!("--user-agent={}", user_agent));
+        }
+        if let Some(proxy) = &self.proxy {
+            args.push(format!("--proxy-server={}", proxy.server()));
+        }
+        if let Some(language) = &self.language {
+            args.push(format!("--lang={}", language));
+            args.push(format!("--accept-lang={}", language));
+        }
+        config.args(args).build()
+    }
+}
@@ -1,5 +1,35 @@
 use std::time::Duration;
 
-pub(crate) const HOST: &str = "https://kagi.com";
-pub(crate) const MAX_RETRIES: u32 = 5;
+pub(crate) const DEFAULT_HOST: &str = "https://kagi.com";
+pub(crate) const RESULTS_TIMEOUT: Duration = Duration::from_secs(5);
 pub(crate) const RETRY_TIMEOUT: Duration = Duration::from_millis(1000);
+pub(crate) const QUICK_ANSWER_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const SUMMARY_TIMEOUT: Duration = Duration::from_secs(60);
+pub(crate) const SEARCH_RESULT_SELECTOR: &str = ".results-box .search-result";
+pub(crate) const MORE_RESULTS_SELECTOR: &str = "#search-more-results, .more-results";
+pub(crate) const DEFAULT_ARGS: &[&str] = &[
+    "--disable-blink-features=AutomationControlled",
+    "--disable-features=IsolateOrigins,site-per-process",
+    "--disable-site-isolation-trials",
+    "--no-sandbox",
+    "--disable-setuid-sandbox",
+    "--disable-dev-shm-usage",
+    "--disable-accelerated-2d-canvas",
+    "--no-first-run",
+    "--no-zygote",
+    "--disable-gpu",
+    "--hide-scrollbars",
+    "--mute-audio",
+    "--disable-background-networking",
+    "--disable-background-timer-throttling",
+    "--disable-backgrounding-occluded-windows",
+    "--disable-breakpad",
+    "--disable-component-extensions-with-background-pages",
+    "--disable-extensions",
+    "--disable-features=TranslateUI",
+    "--disable-ipc-flooding-protection",
+    "--disable-renderer-backgrounding",
+    "--enable-features=NetworkService,NetworkServiceInProcess",
+    "--force-color-profile=srgb",
+    "--metrics-recording-only",
+];
@@ -0,0 +1,135 @@
+use std::{ffi::OsString, path::PathBuf, process::Stdio};
+
+use futures::{channel::oneshot, future::BoxFuture};
+use zeroize::Zeroizing;
+
+use crate::Error;
+
+/// Source of a secret, such as a token or password, fetched only when Kagi asks to sign in.
+///
+/// The secret, and the sign in URL carrying a token, are wiped from memory once the sign in
+/// completed, so they are not kept for the lifetime of the process like the `String`s of
+/// [`AuthType::Token`](crate::AuthType::Token) and [`AuthType::Login`](crate::AuthType::Login).
+/// Copies made while sending the secret to the browser are freed but not wiped, and environment
+/// variables read by [`EnvCredential`] stay in the environment.
+pub trait CredentialProvider: Send + Sync {
+    /// Fetch the secret
+    fn secret(&self) -> BoxFuture<'_, Result<Zeroizing<String>, Error>>;
+}
+
+/// Secret read from an environment variable
+#[derive(Debug, Clone)]
+pub struct EnvCredential {
+    var: String,
+}
+
+impl EnvCredential {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl CredentialProvider for EnvCredential {
+    fn secret(&self) -> BoxFuture<'_, Result<Zeroizing<String>, Error>> {
+        Box::pin(async move {
+            std::env::var(&self.var)
+                .map(Zeroizing::new)
+                .map_err(|e| Error::CredentialUnavailable(format!("{}: {}", self.var, e)))
+        })
+    }
+}
+
+/// Secret read from a file, ignoring the trailing newline
+#[derive(Debug, Clone)]
+pub struct FileCredential {
+    path: PathBuf,
+}
+
+impl FileCredential {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CredentialProvider for FileCredential {
+    fn secret(&self) -> BoxFuture<'_, Result<Zeroizing<String>, Error>> {
+        Box::pin(async move {
+            let secret = std::fs::read_to_string(&self.path).map_err(|e| {
+                Error::CredentialUnavailable(format!("{}: {}", self.path.display(), e))
+            })?;
+            Ok(first_line(Zeroizing::new(secret)))
+        })
+    }
+}
+
+/// Secret printed by a command, such as `pass show kagi/token`, on the first line of its output
+#[derive(Debug, Clone)]
+pub struct CommandCredential {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl CommandCredential {
+    pub fn new(program: impl Into<OsString>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add an argument to the command
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add arguments to the command
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    fn describe(&self) -> String {
+        self.program.to_string_lossy().into_owned()
+    }
+}
+
+impl CredentialProvider for CommandCredential {
+    fn secret(&self) -> BoxFuture<'_, Result<Zeroizing<String>, Error>> {
+        Box::pin(async move {
+            // Run on its own thread so waiting for the command does not block the runtime
+            let (sender, receiver) = oneshot::channel();
+            let mut command = std::process::Command::new(&self.program);
+            command
+                .args(&self.args)
+                .stdin(Stdio::inherit())
+                .stderr(Stdio::inherit());
+            std::thread::spawn(move || {
+                let _ = sender.send(command.output());
+            });
+            let output = receiver
+                .await
+                .map_err(|_| Error::CredentialUnavailable(self.describe()))?
+                .map_err(|e| Error::CredentialUnavailable(format!("{}: {}", self.describe(), e)))?;
+            let stdout = Zeroizing::new(output.stdout);
+            if !output.status.success() {
+                return Err(Error::CredentialUnavailable(format!(
+                    "{}: {}",
+                    self.describe(),
+                    output.status
+                )));
+            }
+            let secret = std::str::from_utf8(&stdout).map_err(|_| {
+                Error::CredentialUnavailable(format!("{}: output is not UTF-8", self.describe()))
+            })?;
+            Ok(first_line(Zeroizing::new(secret.to_string())))
+        })
+    }
+}
+
+/// Truncate `secret` to its first line in place, so no copy is left behind
+fn first_line(mut secret: Zeroizing<String>) -> Zeroizing<String> {
+    let len = secret.lines().next().unwrap_or_default().len();
+    secret.truncate(len);
+    secret
+}
@@ -1,25 +1,8 @@
-use chromiumoxide::error::CdpError;
-
-#[macro_export]
-macro_rules! auth_error {
-    ($($arg:tt)*) => {
-        Error::AuthError(format!($($arg)*))
-    }
-}
+use std::path::PathBuf;
 
-#[macro_export]
-macro_rules! element_error {
-    ($($arg:tt)*) => {
-        Error::ElementNotFound(format!($($arg)*))
-    }
-}
+use chromiumoxide::error::CdpError;
 
-#[macro_export]
-macro_rules! browser_error {
-    ($($arg:tt)*) => {
-        Error::BrowserError(format!($($arg)*))
-    }
-}
+use crate::Phase;
 
 #[macro_export]
 macro_rules! spawner_error {
@@ -30,18 +13,108 @@ macro_rules! spawner_error {
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("Authentication failed: {0}")]
-    AuthError(String),
-    #[error("Element not found: {0}")]
-    ElementNotFound(String),
-    #[error("Browser error: {0}")]
-    BrowserError(String),
+    #[error("Invalid or expired login token")]
+    InvalidToken,
+    #[error("Two-factor authentication code required")]
+    TwoFactorRequired,
+    #[error("Invalid two-factor authentication code")]
+    InvalidTwoFactorCode,
+    #[error("Login rejected, check the email and password")]
+    LoginRejected,
+    #[error("Invalid TOTP secret: {0}")]
+    InvalidTotpSecret(String),
+    #[error("Credential unavailable: {0}")]
+    CredentialUnavailable(String),
+    #[error("Invalid or expired cookies")]
+    InvalidCookies,
+    #[error("Sign in required, which is not supported in incognito mode")]
+    SignInRequired,
+    #[error("Failed to navigate to {url}")]
+    NavigationFailed { url: String },
+    #[error("Element not found: {selector}")]
+    SelectorMissing { selector: String },
+    #[error("Browser closed")]
+    BrowserClosed,
+    #[error("Invalid browser configuration: {0}")]
+    InvalidConfig(String),
     #[error("URL parsing error: {0}")]
     UrlError(#[from] url::ParseError),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("CDP error: {0}")]
-    CdpError(#[from] CdpError),
+    CdpError(CdpError),
     #[error("Spawner error: {0}")]
     SpawnerError(String),
+    #[error("Invalid host: {0}")]
+    InvalidHost(String),
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+    #[error("Profile directory is in use by another process: {}", .0.display())]
+    ProfileLocked(PathBuf),
+    #[error("Timed out during {phase}")]
+    Timeout { phase: Phase },
+}
+
+impl From<CdpError> for Error {
+    /// A lost connection to the browser means it was closed or crashed
+    fn from(e: CdpError) -> Self {
+        match e {
+            CdpError::Ws(_) | CdpError::ChannelSendError(_) => Error::BrowserClosed,
+            e => Error::CdpError(e),
+        }
+    }
+}
+
+impl Error {
+    /// Whether the request may succeed when retried.
+    ///
+    /// Transient failures such as timeouts, failed navigations, a crashed browser or a lost
+    /// connection to it are retryable. Rejected credentials and invalid configuration are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::NavigationFailed { .. }
+            | Error::SelectorMissing { .. }
+            | Error::BrowserClosed
+            | Error::IoError(_)
+            | Error::Timeout { .. } => true,
+            Error::CdpError(e) => matches!(
+                e,
+                CdpError::Io(_)
+                    | CdpError::NoResponse
+                    | CdpError::Timeout
+                    | CdpError::NotFound
+                    | CdpError::FrameNotFound(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the connection to the browser was lost, e.g. because it crashed
+    pub(crate) fn is_connection_lost(&self) -> bool {
+        matches!(
+            self,
+            Error::BrowserClosed | Error::CdpError(CdpError::Io(_) | CdpError::NoResponse)
+        )
+    }
+
+    pub(crate) fn selector_missing(selector: &str) -> Self {
+        Error::SelectorMissing {
+            selector: selector.to_string(),
+        }
+    }
+
+    /// Error of a failed lookup of `selector`. Chrome rejects the lookup of a missing element,
+    /// other failures such as a lost connection are kept as is.
+    pub(crate) fn lookup(selector: &str, e: CdpError) -> Self {
+        if is_missing(&e) {
+            Error::selector_missing(selector)
+        } else {
+            e.into()
+        }
+    }
+}
+
+/// Whether an element lookup failed because no element matched
+pub(crate) fn is_missing(e: &CdpError) -> bool {
+    matches!(e, CdpError::Chrome(_) | CdpError::NotFound)
 }
@@ -0,0 +1,116 @@
+use tracing::debug;
+
+use crate::{
+    AuthType, Error, Kagi, SearchOptions,
+    error::is_missing,
+    timeout::{deadline, until},
+};
+
+/// Kagi lens restricting a search to a set of sources
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lens {
+    /// Discussion forums such as Reddit and Hacker News
+    Forums,
+    /// Programming documentation, blogs and Q&A sites
+    Programming,
+    /// World news outlets
+    WorldNews,
+    /// Academic papers and research
+    Academic,
+    /// PDF documents
+    Pdfs,
+    /// Non-commercial websites
+    NonCommercial,
+    /// Kagi's Small Web index
+    SmallWeb,
+    /// Any lens by its id, as returned by [`Kagi::list_lenses`]
+    Custom(String),
+}
+
+impl Lens {
+    /// The lens id used in the `l` search parameter
+    pub fn id(&self) -> &str {
+        match self {
+            Lens::Forums => "1",
+            Lens::Programming => "2",
+            Lens::WorldNews => "3",
+            Lens::Academic => "4",
+            Lens::Pdfs => "5",
+            Lens::NonCommercial => "6",
+            Lens::SmallWeb => "7",
+            Lens::Custom(id) => id,
+        }
+    }
+}
+
+/// Lens available to the signed in user
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct LensInfo {
+    /// Lens id, usable with [`Lens::Custom`]
+    pub id: String,
+    /// Display name of the lens
+    pub name: String,
+    /// Whether the lens is enabled in the search bar, `None` if the page does not show it
+    pub enabled: Option<bool>,
+}
+
+impl Kagi {
+    /// List the lenses available to the user, as shown on the lens settings page.
+    ///
+    /// # Parameters
+    ///
+    /// - `auth_type`: Optional authentication type to use for this request performing in incognito
+    ///   mode. `AuthType::Cookies` and `AuthType::Icognito` cannot sign in, so they are not
+    ///   supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Page initialization fails
+    /// - Navigation to the settings page fails
+    /// - Authentication fails
+    ///
+    pub async fn list_lenses(&self, auth_type: Option<AuthType>) -> Result<Vec<LensInfo>, Error> {
+        let timeouts = self.timeouts(&SearchOptions::default());
+        until(deadline(&timeouts), async {
+            let mut url = self.url("settings")?;
+            url.query_pairs_mut().append_pair("p", "lenses");
+            let page = self
+                .open_page(url, auth_type, &SearchOptions::default())
+                .await?;
+
+            let mut lenses = Vec::new();
+            for element in page.inner().find_elements("[data-lens-id]").await? {
+                let Some(id) = element.attribute("data-lens-id").await? else {
+                    debug!("Lens id not found");
+                    continue;
+                };
+                let name = match element.find_element(".lens-name").await {
+                    Ok(name) => name.inner_text().await?,
+                    Err(_) => element.inner_text().await?,
+                };
+                let Some(name) = name else {
+                    debug!("Lens name not found");
+                    continue;
+                };
+                let enabled = match element.find_element("input[type='checkbox']").await {
+                    Ok(checkbox) => checkbox
+                        .property("checked")
+                        .await?
+                        .and_then(|checked| checked.as_bool()),
+                    Err(e) if is_missing(&e) => None,
+                    Err(e) => return Err(e.into()),
+                };
+                lenses.push(LensInfo {
+                    id,
+                    name: name.trim().to_string(),
+                    enabled,
+                });
+            }
+            Ok(lenses)
+        })
+        .await
+    }
+}
@@ -1,9 +1,32 @@
 mod auth;
+mod builder;
 mod consts;
+mod credentials;
 mod error;
+mod lens;
+mod options;
 mod page;
+mod pool;
+mod profile;
+mod proxy;
+mod response;
 mod search;
+#[cfg(feature = "server")]
+pub mod server;
 mod spawner;
+mod summarize;
+mod timeout;
+mod vertical;
 
+pub use auth::*;
+pub use builder::*;
+pub use credentials::*;
 pub use error::*;
+pub use lens::*;
+pub use options::*;
+pub use proxy::*;
+pub use response::*;
 pub use search::*;
+pub use summarize::*;
+pub use timeout::*;
+pub use vertical::*;
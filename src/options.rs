@@ -0,0 +1,203 @@
+use crate::{Lens, Proxy, Timeouts};
+
+/// Time range filter applied to search results
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeRange {
+    /// Results from the past 24 hours
+    Day,
+    /// Results from the past week
+    Week,
+    /// Results from the past month
+    Month,
+    /// Results from the past year
+    Year,
+}
+
+impl TimeRange {
+    fn as_param(&self) -> &'static str {
+        match self {
+            TimeRange::Day => "1",
+            TimeRange::Week => "2",
+            TimeRange::Month => "3",
+            TimeRange::Year => "4",
+        }
+    }
+}
+
+/// Safe search mode
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeSearch {
+    /// Filter explicit content
+    On,
+    /// Do not filter explicit content
+    Off,
+}
+
+impl SafeSearch {
+    fn as_param(&self) -> &'static str {
+        match self {
+            SafeSearch::On => "1",
+            SafeSearch::Off => "0",
+        }
+    }
+}
+
+/// Sort order of search results
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Kagi's default ranking
+    Default,
+    /// Most recent results first
+    Recency,
+    /// Group results by website
+    Website,
+    /// Results with the fewest trackers first
+    Trackers,
+}
+
+impl SortOrder {
+    fn as_param(&self) -> &'static str {
+        match self {
+            SortOrder::Default => "1",
+            SortOrder::Recency => "2",
+            SortOrder::Website => "3",
+            SortOrder::Trackers => "4",
+        }
+    }
+}
+
+/// Options applied to a single search
+///
+/// Every field maps to a `/search` URL parameter understood by Kagi. Fields left as `None`
+/// fall back to the account settings.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Only return results published within this time range
+    pub time_range: Option<TimeRange>,
+    /// Region code, e.g. `us`, `de` or `no_region`
+    pub region: Option<String>,
+    /// Safe search mode
+    pub safe_search: Option<SafeSearch>,
+    /// Sort order of the results
+    pub order: Option<SortOrder>,
+    /// Match the query literally, disabling query expansion
+    pub verbatim: bool,
+    /// 1-based results page to start from, Kagi calls these batches
+    ///
+    /// Further pages are still loaded through "More results" until the limit is satisfied.
+    pub page: Option<u32>,
+    /// Restrict the search to a lens
+    pub lens: Option<Lens>,
+    /// Route this search through a proxy, using a dedicated browser context
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub proxy: Option<Proxy>,
+    /// Override the timeouts of the instance for this search
+    pub timeouts: Option<Timeouts>,
+}
+
+impl SearchOptions {
+    /// Encode the options as `/search` URL parameters
+    pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(time_range) = &self.time_range {
+            pairs.push(("dr", time_range.as_param().to_string()));
+        }
+        if let Some(region) = &self.region {
+            pairs.push(("r", region.clone()));
+        }
+        if let Some(safe_search) = &self.safe_search {
+            pairs.push(("safe_search", safe_search.as_param().to_string()));
+        }
+        if let Some(order) = &self.order {
+            pairs.push(("order", order.as_param().to_string()));
+        }
+        if self.verbatim {
+            pairs.push(("verbatim", "1".to_string()));
+        }
+        if let Some(lens) = &self.lens {
+            pairs.push(("l", lens.id().to_string()));
+        }
+        if let Some(page) = self.page.filter(|page| *page > 1) {
+            pairs.push(("batch", page.to_string()));
+        }
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_emit_no_pairs() {
+        assert!(SearchOptions::default().query_pairs().is_empty());
+    }
+
+    #[test]
+    fn test_query_pairs() {
+        let options = SearchOptions {
+            time_range: Some(TimeRange::Week),
+            region: Some("de".to_string()),
+            safe_search: Some(SafeSearch::Off),
+            order: Some(SortOrder::Recency),
+            verbatim: true,
+            page: Some(3),
+            lens: Some(Lens::Programming),
+            ..Default::default()
+        };
+        let pairs: Vec<_> = options
+            .query_pairs()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                "dr=2",
+                "r=de",
+                "safe_search=0",
+                "order=2",
+                "verbatim=1",
+                "l=2",
+                "batch=3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_page_emits_no_batch() {
+        for page in [None, Some(0), Some(1)] {
+            let options = SearchOptions {
+                page,
+                ..Default::default()
+            };
+            assert!(options.query_pairs().is_empty(), "page {:?}", page);
+        }
+    }
+
+    #[test]
+    fn test_param_values() {
+        let ranges = [
+            TimeRange::Day,
+            TimeRange::Week,
+            TimeRange::Month,
+            TimeRange::Year,
+        ];
+        let orders = [
+            SortOrder::Default,
+            SortOrder::Recency,
+            SortOrder::Website,
+            SortOrder::Trackers,
+        ];
+        for (i, (range, order)) in ranges.iter().zip(orders).enumerate() {
+            assert_eq!(range.as_param(), (i + 1).to_string());
+            assert_eq!(order.as_param(), (i + 1).to_string());
+        }
+        assert_eq!(SafeSearch::On.as_param(), "1");
+        assert_eq!(SafeSearch::Off.as_param(), "0");
+    }
+}
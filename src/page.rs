@@ -1,13 +1,19 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use chromiumoxide::{Browser, cdp::browser_protocol::browser::BrowserContextId};
+use chromiumoxide::{Browser, Element, cdp::browser_protocol::browser::BrowserContextId};
+use futures_timer::Delay;
 use tracing::debug;
 
-use crate::spawner::spawn;
+use crate::{Error, consts::RETRY_TIMEOUT, pool::Lease, spawner::spawn};
 
 pub(crate) struct Page {
     page: chromiumoxide::Page,
     context_id: Option<BrowserContextId>,
+    /// Slot of the page pool held while the page is in use
+    lease: Option<Lease>,
     #[cfg(feature = "tokio-runtime")]
     browser: Arc<tokio::sync::RwLock<Browser>>,
     #[cfg(feature = "async-std-runtime")]
@@ -24,13 +30,71 @@ impl Page {
         Self {
             page,
             context_id,
+            lease: None,
             browser,
         }
     }
 
+    /// Hold `lease` until the page is dropped. If the page is the leased tab itself, it is
+    /// returned to the pool instead of being closed.
+    pub fn with_lease(mut self, lease: Lease) -> Self {
+        self.lease = Some(lease);
+        self
+    }
+
     pub fn inner(&self) -> &chromiumoxide::Page {
         &self.page
     }
+
+    /// Wait until at least one element matches `selector`, returning `None` if none appear
+    /// within `timeout`
+    pub async fn wait_for_elements(
+        &self,
+        selector: &str,
+        timeout: Duration,
+    ) -> Result<Option<Vec<Element>>, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let elements = self.page.find_elements(selector).await?;
+            if !elements.is_empty() {
+                return Ok(Some(elements));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            debug!("No elements matching {} found, waiting", selector);
+            Delay::new(RETRY_TIMEOUT).await;
+        }
+    }
+
+    /// Wait for the element matching `selector` to finish streaming, i.e. its text stops
+    /// changing between two polls. Returns `None` if it does not settle within `timeout`.
+    pub async fn wait_for_stable_element(
+        &self,
+        selector: &str,
+        timeout: Duration,
+    ) -> Result<Option<Element>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut previous: Option<String> = None;
+        while Instant::now() < deadline {
+            Delay::new(RETRY_TIMEOUT).await;
+            let Ok(element) = self.page.find_element(selector).await else {
+                debug!("{} not rendered yet, waiting", selector);
+                continue;
+            };
+            let text = element.inner_text().await?.unwrap_or_default();
+            if text.trim().is_empty() {
+                debug!("{} empty, waiting", selector);
+                continue;
+            }
+            if previous.as_deref() == Some(text.as_str()) {
+                return Ok(Some(element));
+            }
+            debug!("{} still streaming, waiting", selector);
+            previous = Some(text);
+        }
+        Ok(None)
+    }
 }
 
 impl Drop for Page {
@@ -48,6 +112,45 @@ impl Drop for Page {
                     debug!("Failed to dispose browser context: {:?}", e);
                 }
             });
+        } else if self
+            .lease
+            .as_ref()
+            .is_some_and(|lease| lease.tab().target_id() == self.page.target_id())
+        {
+            debug!("Keeping pooled tab open");
+        } else {
+            // Pages in the default context outlive the instance when attached to an external
+            // browser, so close them explicitly
+            let page = self.page.clone();
+            spawn(async move {
+                if let Err(e) = page.close().await {
+                    debug!("Failed to close page: {:?}", e);
+                }
+            });
         }
     }
 }
+
+/// Trimmed inner text of the first child matching `selector`
+pub(crate) async fn find_text(element: &Element, selector: &str) -> Result<Option<String>, Error> {
+    let Ok(child) = element.find_element(selector).await else {
+        return Ok(None);
+    };
+    Ok(child
+        .inner_text()
+        .await?
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty()))
+}
+
+/// Attribute of the first child matching `selector`
+pub(crate) async fn find_attribute(
+    element: &Element,
+    selector: &str,
+    attribute: &str,
+) -> Result<Option<String>, Error> {
+    let Ok(child) = element.find_element(selector).await else {
+        return Ok(None);
+    };
+    Ok(child.attribute(attribute).await?)
+}
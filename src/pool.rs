@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use futures::{
+    StreamExt,
+    channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+    lock::Mutex,
+};
+use tracing::debug;
+
+use crate::{Error, spawner::spawn};
+
+/// Fixed set of tabs shared by concurrent requests.
+///
+/// Each tab is handed out to one request at a time, callers wait in line while all of them are
+/// busy. Tabs are tagged with the generation of the browser they were opened in, so tabs of a
+/// crashed browser can be replaced after a relaunch.
+pub(crate) struct PagePool {
+    idle: UnboundedSender<(usize, chromiumoxide::Page)>,
+    queue: Mutex<UnboundedReceiver<(usize, chromiumoxide::Page)>>,
+}
+
+impl PagePool {
+    pub(crate) fn new(tabs: Vec<chromiumoxide::Page>) -> Self {
+        let (idle, queue) = unbounded();
+        for tab in tabs {
+            let _ = idle.unbounded_send((0, tab));
+        }
+        Self {
+            idle,
+            queue: Mutex::new(queue),
+        }
+    }
+
+    /// Wait until a tab is idle and lease it
+    pub(crate) async fn acquire(self: &Arc<Self>) -> Result<Lease, Error> {
+        let mut queue = self.queue.lock().await;
+        debug!("Waiting for an idle tab");
+        let Some((generation, tab)) = queue.next().await else {
+            return Err(Error::BrowserClosed);
+        };
+        Ok(Lease {
+            generation,
+            tab,
+            pool: self.clone(),
+        })
+    }
+
+    /// Close the idle tabs. Leased tabs are closed once returned, since the pool no longer
+    /// takes them back.
+    pub(crate) async fn close(&self) {
+        self.idle.close_channel();
+        let mut queue = self.queue.lock().await;
+        while let Ok(Some((_, tab))) = queue.try_next() {
+            if let Err(e) = tab.close().await {
+                debug!("Failed to close pooled tab: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Tab leased from a [`PagePool`], returned to the pool when dropped
+pub(crate) struct Lease {
+    generation: usize,
+    tab: chromiumoxide::Page,
+    pool: Arc<PagePool>,
+}
+
+impl Lease {
+    pub(crate) fn tab(&self) -> &chromiumoxide::Page {
+        &self.tab
+    }
+
+    /// Generation of the browser the tab was opened in
+    pub(crate) fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Swap the tab for one opened in the browser of `generation`
+    pub(crate) fn replace(&mut self, tab: chromiumoxide::Page, generation: usize) {
+        self.tab = tab;
+        self.generation = generation;
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        debug!("Returning tab to pool");
+        if self
+            .pool
+            .idle
+            .unbounded_send((self.generation, self.tab.clone()))
+            .is_err()
+        {
+            let tab = self.tab.clone();
+            spawn(async move {
+                if let Err(e) = tab.close().await {
+                    debug!("Failed to close pooled tab: {:?}", e);
+                }
+            });
+        }
+    }
+}
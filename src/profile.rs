@@ -0,0 +1,60 @@
+use std::{
+    fs::{File, TryLockError},
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+const LOCK_FILE: &str = "kagisearch.lock";
+
+/// Exclusive lock on a persistent browser profile directory, released on drop
+pub(crate) struct ProfileLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl ProfileLock {
+    /// Lock `user_data_dir`, creating it if needed.
+    ///
+    /// Returns `None` if another process already uses the profile.
+    pub fn acquire(user_data_dir: &Path) -> std::io::Result<Option<Self>> {
+        std::fs::create_dir_all(user_data_dir)?;
+        let path = user_data_dir.join(LOCK_FILE);
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+        debug!("Locked browser profile: {}", user_data_dir.display());
+        Ok(Some(Self { _file: file, path }))
+    }
+}
+
+impl Drop for ProfileLock {
+    fn drop(&mut self) {
+        debug!("Releasing browser profile lock: {}", self.path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_twice() {
+        let dir = std::env::temp_dir().join(format!("kagisearch-profile-{}", std::process::id()));
+
+        let lock = ProfileLock::acquire(&dir).unwrap();
+        assert!(lock.is_some());
+        assert!(ProfileLock::acquire(&dir).unwrap().is_none());
+        drop(lock);
+        assert!(ProfileLock::acquire(&dir).unwrap().is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
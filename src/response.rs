@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use tracing::debug;
+
+use crate::{
+    Error, Phase, SearchResult,
+    page::{Page, find_attribute, find_text},
+};
+
+const QUICK_ANSWER_SELECTOR: &str = ".quick-answer";
+const QUICK_ANSWER_CONTENT_SELECTOR: &str = ".quick-answer .qa-content";
+const QUICK_ANSWER_REFERENCE_SELECTOR: &str = ".quick-answer .qa-references a[href]";
+const RELATED_SEARCHES_SELECTOR: &str = ".related-searches a";
+const CORRECTED_QUERY_SELECTOR: &str = ".did-you-mean a, .spelling-correction a";
+const INFOBOX_SELECTOR: &str = ".infobox";
+const INTERESTING_FINDS_SELECTOR: &str = ".interesting-finds a[href]";
+const LISTICLES_SELECTOR: &str = ".listicles a[href]";
+const DISCUSSIONS_SELECTOR: &str = ".discussions a[href]";
+
+/// Converts the rendered answer back into markdown
+const TO_MARKDOWN_FN: &str = r##"function() {
+    const walk = (node) => {
+        if (node.nodeType === Node.TEXT_NODE) return node.textContent;
+        if (node.nodeType !== Node.ELEMENT_NODE) return "";
+        const inner = Array.from(node.childNodes).map(walk).join("");
+        switch (node.tagName) {
+            case "H1": case "H2": case "H3": case "H4": case "H5": case "H6":
+                return "#".repeat(Number(node.tagName[1])) + " " + inner.trim() + "\n\n";
+            case "P": return inner.trim() + "\n\n";
+            case "BR": return "\n";
+            case "STRONG": case "B": return "**" + inner + "**";
+            case "EM": case "I": return "*" + inner + "*";
+            case "CODE": return node.parentElement && node.parentElement.tagName === "PRE" ? inner : "`" + inner + "`";
+            case "PRE": return "```\n" + inner.trim() + "\n```\n\n";
+            case "A": return "[" + inner + "](" + node.href + ")";
+            case "LI": return (node.parentElement && node.parentElement.tagName === "OL" ? "1. " : "- ") + inner.trim() + "\n";
+            case "UL": case "OL": return inner + "\n";
+            default: return inner;
+        }
+    };
+    return walk(this).replace(/\n{3,}/g, "\n\n").trim();
+}"##;
+
+/// Search results together with the rest of the search page
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Default)]
+pub struct SearchResponse {
+    /// Search results
+    pub results: Vec<SearchResult>,
+    /// Quick Answer generated for the query, if any
+    pub quick_answer: Option<QuickAnswer>,
+    /// Related searches suggested by Kagi
+    pub related_searches: Vec<String>,
+    /// Wikipedia or knowledge panel shown next to the results
+    pub infobox: Option<Infobox>,
+    /// Links from the "Interesting finds" section
+    pub interesting_finds: Vec<Link>,
+    /// Links from the "Listicles" section
+    pub listicles: Vec<Link>,
+    /// Links from the "Discussions" section
+    pub discussions: Vec<Link>,
+    /// Query Kagi searched for instead, or suggested with "Did you mean"
+    pub corrected_query: Option<String>,
+    /// Total time spent performing the search
+    pub elapsed: Duration,
+}
+
+/// Link shown in a grouped section of the search page
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct Link {
+    /// Title of the link
+    pub title: String,
+    /// URL of the link
+    pub url: String,
+}
+
+/// Wikipedia or knowledge panel
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct Infobox {
+    /// Title of the panel
+    pub title: String,
+    /// Description of the subject
+    pub description: Option<String>,
+    /// URL of the source, usually Wikipedia
+    pub url: Option<String>,
+    /// URL of the image shown in the panel
+    pub image: Option<String>,
+    /// Facts listed in the panel as label and value pairs
+    pub facts: Vec<(String, String)>,
+}
+
+/// Quick Answer generated by Kagi for questions
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct QuickAnswer {
+    /// Answer formatted as markdown
+    pub markdown: String,
+    /// URLs of the references cited by the answer
+    pub references: Vec<String>,
+}
+
+/// Extract the Quick Answer, waiting up to `timeout` for it to appear and finish streaming when
+/// `wait` is set
+pub(crate) async fn extract_quick_answer(
+    page: &Page,
+    wait: bool,
+    timeout: Duration,
+) -> Result<Option<QuickAnswer>, Error> {
+    if !wait
+        && page
+            .inner()
+            .find_element(QUICK_ANSWER_SELECTOR)
+            .await
+            .is_err()
+    {
+        return Ok(None);
+    }
+    let Some(content) = page
+        .wait_for_stable_element(QUICK_ANSWER_CONTENT_SELECTOR, timeout)
+        .await?
+    else {
+        // An expected answer that rendered but kept streaming would be cut off
+        if wait
+            && page
+                .inner()
+                .find_element(QUICK_ANSWER_SELECTOR)
+                .await
+                .is_ok()
+        {
+            return Err(Error::Timeout {
+                phase: Phase::QuickAnswer,
+            });
+        }
+        debug!("Quick Answer not found");
+        return Ok(None);
+    };
+    let rendered = content
+        .call_js_fn(TO_MARKDOWN_FN, false)
+        .await?
+        .result
+        .value;
+    let markdown = match rendered.as_ref().and_then(|value| value.as_str()) {
+        Some(markdown) => markdown.to_string(),
+        None => content.inner_text().await?.unwrap_or_default(),
+    };
+    let mut references = Vec::new();
+    for reference in page
+        .inner()
+        .find_elements(QUICK_ANSWER_REFERENCE_SELECTOR)
+        .await?
+    {
+        if let Some(href) = reference.attribute("href").await?
+            && !references.contains(&href)
+        {
+            references.push(href);
+        }
+    }
+    Ok(Some(QuickAnswer {
+        markdown,
+        references,
+    }))
+}
+
+/// Extract everything on the search page besides the results and the Quick Answer
+pub(crate) async fn extract_sections(page: &Page) -> Result<SearchResponse, Error> {
+    let mut related_searches = Vec::new();
+    for element in page
+        .inner()
+        .find_elements(RELATED_SEARCHES_SELECTOR)
+        .await?
+    {
+        if let Some(text) = element.inner_text().await? {
+            let text = text.trim();
+            if !text.is_empty() {
+                related_searches.push(text.to_string());
+            }
+        }
+    }
+    let corrected_query = match page.inner().find_element(CORRECTED_QUERY_SELECTOR).await {
+        Ok(element) => element
+            .inner_text()
+            .await?
+            .map(|text| text.trim().to_string()),
+        Err(_) => None,
+    };
+    Ok(SearchResponse {
+        related_searches,
+        infobox: extract_infobox(page).await?,
+        interesting_finds: extract_links(page, INTERESTING_FINDS_SELECTOR).await?,
+        listicles: extract_links(page, LISTICLES_SELECTOR).await?,
+        discussions: extract_links(page, DISCUSSIONS_SELECTOR).await?,
+        corrected_query,
+        ..Default::default()
+    })
+}
+
+async fn extract_infobox(page: &Page) -> Result<Option<Infobox>, Error> {
+    let Ok(infobox) = page.inner().find_element(INFOBOX_SELECTOR).await else {
+        return Ok(None);
+    };
+    let Some(title) = find_text(&infobox, ".infobox-title").await? else {
+        debug!("Infobox title not found");
+        return Ok(None);
+    };
+    let mut facts = Vec::new();
+    for row in infobox.find_elements(".infobox-facts tr").await? {
+        if let (Some(label), Some(value)) =
+            (find_text(&row, "th").await?, find_text(&row, "td").await?)
+        {
+            facts.push((label, value));
+        }
+    }
+    Ok(Some(Infobox {
+        title,
+        description: find_text(&infobox, ".infobox-description").await?,
+        url: find_attribute(&infobox, ".infobox-source a", "href").await?,
+        image: find_attribute(&infobox, "img", "src").await?,
+        facts,
+    }))
+}
+
+/// Extract the distinct links matching `selector`
+async fn extract_links(page: &Page, selector: &str) -> Result<Vec<Link>, Error> {
+    let mut links: Vec<Link> = Vec::new();
+    for element in page.inner().find_elements(selector).await? {
+        let (Some(title), Some(url)) = (
+            element.inner_text().await?,
+            element.attribute("href").await?,
+        ) else {
+            continue;
+        };
+        let title = title.trim();
+        if title.is_empty() || links.iter().any(|link| link.url == url) {
+            continue;
+        }
+        links.push(Link {
+            title: title.to_string(),
+            url,
+        });
+    }
+    Ok(links)
+}
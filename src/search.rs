@@ -1,46 +1,85 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use chromiumoxide::{
-    BrowserConfig, Element,
+    BrowserConfig, Element, Handler,
     browser::Browser,
     cdp::browser_protocol::{
-        network::{Cookie, CookieParam},
+        network::{Cookie, CookieParam, SetCookiesParams, TimeSinceEpoch},
         target::{CreateBrowserContextParams, CreateTargetParams},
     },
-    handler::viewport::Viewport,
 };
-use futures::StreamExt;
+use futures::{Stream, StreamExt, stream};
 use futures_timer::Delay;
-use tracing::debug;
+use tracing::{debug, warn};
 use url::Url;
 
 use crate::{
-    Error,
-    auth::{handle_signin, handle_token},
-    auth_error, browser_error,
-    consts::{HOST, MAX_RETRIES, RETRY_TIMEOUT},
-    page::Page,
+    CredentialProvider, Error, Proxy,
+    auth::{TwoFactor, TwoFactorCode, handle_signin, handle_token},
+    builder::KagiBuilder,
+    consts::{MORE_RESULTS_SELECTOR, RETRY_TIMEOUT, SEARCH_RESULT_SELECTOR},
+    error::is_missing,
+    options::SearchOptions,
+    page::{Page, find_attribute, find_text},
+    pool::PagePool,
+    profile::ProfileLock,
+    response::{Link, SearchResponse, extract_quick_answer, extract_sections},
     spawner::spawn,
+    timeout::{Phase, Timeouts, deadline, timeout, until},
 };
 
+/// Selector of the sitelinks shown below a search result
+const SITELINK_SELECTOR: &str = ".__srgi-title a[href]";
+/// Whether a search result is nested under "More results from this site"
+const GROUPED_FN: &str = "function() { return this.closest('.sr-group') !== null; }";
+
 /// Search result
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Debug)]
 pub struct SearchResult {
+    /// 1-based position of the result among the returned results
+    pub rank: usize,
     /// Title of the search result
     pub title: String,
     /// URL of the search result
     pub url: String,
+    /// Host of the URL
+    pub domain: Option<String>,
     /// Snippet of the search result
     pub snippet: String,
+    /// Publish date shown in the snippet, e.g. `3 days ago`
+    pub published: Option<String>,
+    /// URL of the favicon shown next to the result
+    pub favicon: Option<String>,
+    /// Sub-results and sitelinks listed below the result
+    pub sitelinks: Vec<Link>,
+    /// Whether the result is grouped under "More results from this site"
+    pub grouped: bool,
 }
 
 /// Authentication type
+#[derive(Clone)]
 pub enum AuthType {
-    /// Login with username, password and optional 2FA code
-    Login(String, String, Option<String>),
+    /// Login with username, password and the 2FA code, if enabled. The code can be fixed or
+    /// asked for from a [`TwoFactorProvider`](crate::TwoFactorProvider) once Kagi requires it
+    Login(String, String, Option<TwoFactorCode>),
+    /// Login with username, password and the base32 TOTP secret of the 2FA, generating a fresh
+    /// code whenever Kagi asks for one
+    LoginTotp(String, String, String),
     /// Login with a token
     Token(String),
+    /// Login with a token fetched from the provider whenever Kagi asks to sign in
+    TokenFrom(Arc<dyn CredentialProvider>),
+    /// Login with username, the password fetched from the provider whenever Kagi asks to sign in,
+    /// and the 2FA code, if enabled
+    LoginFrom(String, Arc<dyn CredentialProvider>, Option<TwoFactorCode>),
     /// Load cookies
     Cookies(Vec<CookieParam>),
     /// Use incognito mode
@@ -48,8 +87,33 @@ pub enum AuthType {
 }
 
 /// Browser instance
+///
+/// A launched browser that crashes is relaunched with the same configuration on the next
+/// request, restoring the cookies of the last successful search. A search interrupted by the
+/// crash is retried once.
 pub struct Kagi {
     auth_type: AuthType,
+    /// Whether the browser was attached with [`Kagi::connect`] rather than launched
+    external: bool,
+    _profile: Option<ProfileLock>,
+    /// Proxy the browser was launched with, used to answer its authentication challenges
+    proxy: Option<Proxy>,
+    /// Base URL of Kagi ending with `/`, see [`KagiBuilder::host`]
+    host: Url,
+    /// Default time limits, see [`KagiBuilder::timeouts`]
+    timeouts: Timeouts,
+    /// Tabs shared by concurrent requests, see [`KagiBuilder::pool_size`]
+    pool: Option<Arc<PagePool>>,
+    /// Configuration to relaunch the browser with after a crash, `None` for external browsers
+    launch_config: Option<BrowserConfig>,
+    /// Cleared by the handler task once the current browser exits
+    alive: Mutex<Arc<AtomicBool>>,
+    /// Incremented on every relaunch
+    generation: AtomicUsize,
+    /// Set by [`Kagi::close`] so an intentionally closed browser is not relaunched
+    closed: AtomicBool,
+    /// Cookies of the last successful request, restored after a relaunch
+    session: Mutex<Vec<CookieParam>>,
     #[cfg(feature = "tokio-runtime")]
     browser: Arc<tokio::sync::RwLock<Browser>>,
     #[cfg(feature = "async-std-runtime")]
@@ -61,13 +125,17 @@ impl Kagi {
     ///
     /// This method initializes a new headless browser instance with pre-configured settings optimized
     /// for web scraping. It requires a spawner implementation to handle browser events in the background.
+    /// Use [`Kagi::builder`] to customize the launch configuration.
     ///
     /// # Authentication Types
     ///
-    /// The browser can be authenticated in three ways:
-    /// - Using email and password (with optional 2FA)
-    /// - Using a Kagi login token
-    /// - Using pre-saved cookies
+    /// The browser can be authenticated in these ways:
+    /// - Using email and password, with a fixed or requested 2FA code (`AuthType::Login`) or a
+    ///   TOTP secret generating it (`AuthType::LoginTotp`)
+    /// - Using a Kagi login token (`AuthType::Token`)
+    /// - Using a token or password fetched from a [`CredentialProvider`] on sign in
+    ///   (`AuthType::TokenFrom`, `AuthType::LoginFrom`)
+    /// - Using pre-saved cookies (`AuthType::Cookies`)
     ///
     /// # Errors
     ///
@@ -76,96 +144,264 @@ impl Kagi {
     /// - Cookie loading fails (when using `AuthType::Cookies`)
     ///
     pub async fn new(auth_type: AuthType) -> Result<Self, Error> {
-        let viewport = Viewport {
-            width: 1920,
-            height: 1080,
-            ..Default::default()
+        Self::builder().auth_type(auth_type).build().await
+    }
+
+    /// Create a new browser instance that keeps its state in a persistent profile directory.
+    ///
+    /// Unlike [`Kagi::new`], which starts every browser in incognito mode, the session cookies are
+    /// stored in `user_data_dir` and survive restarts, so signing in is only needed once the
+    /// session expires. The directory is created if needed and locked for the lifetime of the
+    /// instance, so two processes cannot use the same profile concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The profile directory is used by another process
+    /// - Browser initialization fails
+    /// - Cookie loading fails (when using `AuthType::Cookies`)
+    ///
+    pub async fn with_profile(
+        auth_type: AuthType,
+        user_data_dir: impl AsRef<Path>,
+    ) -> Result<Self, Error> {
+        Self::builder()
+            .auth_type(auth_type)
+            .user_data_dir(user_data_dir)
+            .build()
+            .await
+    }
+
+    /// Create a builder to configure how the browser is launched
+    pub fn builder() -> KagiBuilder {
+        KagiBuilder::default()
+    }
+
+    pub(crate) async fn launch(builder: KagiBuilder) -> Result<Self, Error> {
+        let host = builder.base_url().map_err(Error::InvalidHost)?;
+        let profile = match &builder.user_data_dir {
+            Some(user_data_dir) => Some(
+                ProfileLock::acquire(user_data_dir)?
+                    .ok_or_else(|| Error::ProfileLocked(user_data_dir.to_path_buf()))?,
+            ),
+            None => None,
         };
-        let (browser, mut handler) = Browser::launch(
-            BrowserConfig::builder()
-                .viewport(viewport)
-                .incognito()
-                .args([
-                    "--disable-blink-features=AutomationControlled",
-                    "--disable-features=IsolateOrigins,site-per-process",
-                    "--disable-site-isolation-trials",
-                    "--no-sandbox",
-                    "--disable-setuid-sandbox",
-                    "--disable-dev-shm-usage",
-                    "--disable-accelerated-2d-canvas",
-                    "--no-first-run",
-                    "--no-zygote",
-                    "--disable-gpu",
-                    "--hide-scrollbars",
-                    "--mute-audio",
-                    "--disable-background-networking",
-                    "--disable-background-timer-throttling",
-                    "--disable-backgrounding-occluded-windows",
-                    "--disable-breakpad",
-                    "--disable-component-extensions-with-background-pages",
-                    "--disable-extensions",
-                    "--disable-features=TranslateUI",
-                    "--disable-ipc-flooding-protection",
-                    "--disable-renderer-backgrounding",
-                    "--enable-features=NetworkService,NetworkServiceInProcess",
-                    "--force-color-profile=srgb",
-                    "--metrics-recording-only",
-                ])
-                .build()
-                .map_err(|e| browser_error!("{}", e))?,
-        )
-        .await?;
-        spawn(async move {
-            while let Some(h) = handler.next().await {
-                match h {
-                    Ok(_) => continue,
-                    Err(e) => {
-                        debug!("Browser handler error: {}", e);
-                        if e.to_string().contains("Browser closed") {
-                            break;
-                        }
-                    }
-                }
-            }
-            debug!("Browser handler stopped");
-        });
+        let config = builder.browser_config().map_err(Error::InvalidConfig)?;
+        let (browser, handler) = Browser::launch(config.clone()).await?;
+        Self::attach(browser, handler, builder, host, profile, Some(config)).await
+    }
+
+    /// Attach to an already running browser over its DevTools websocket.
+    ///
+    /// Instead of launching a browser, this connects to the one listening on `ws_url`, e.g.
+    /// `ws://127.0.0.1:9222/devtools/browser/<id>` as printed by Chrome when started with
+    /// `--remote-debugging-port`. [`Kagi::close`] only detaches from such a browser and leaves it
+    /// running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Connecting to the browser fails
+    /// - Cookie loading fails (when using `AuthType::Cookies`)
+    ///
+    pub async fn connect(ws_url: impl Into<String>, auth_type: AuthType) -> Result<Self, Error> {
+        Self::builder().auth_type(auth_type).connect(ws_url).await
+    }
+
+    pub(crate) async fn connect_with(builder: KagiBuilder, ws_url: String) -> Result<Self, Error> {
+        let host = builder.base_url().map_err(Error::InvalidHost)?;
+        let (browser, handler) = Browser::connect(ws_url).await?;
+        debug!("Connected to browser: {}", browser.websocket_address());
+        Self::attach(browser, handler, builder, host, None, None).await
+    }
+
+    /// Drive the browser events in the background, load the initial cookies and warm up the
+    /// page pool.
+    ///
+    /// `launch_config` is the configuration the browser was launched with, `None` for an external
+    /// browser.
+    async fn attach(
+        browser: Browser,
+        handler: Handler,
+        builder: KagiBuilder,
+        host: Url,
+        profile: Option<ProfileLock>,
+        launch_config: Option<BrowserConfig>,
+    ) -> Result<Self, Error> {
+        let KagiBuilder {
+            auth_type,
+            proxy,
+            pool_size,
+            timeouts,
+            ..
+        } = builder;
+        let alive = drive(handler);
+        let mut session = Vec::new();
         if let AuthType::Cookies(cookies) = &auth_type {
             browser.set_cookies(cookies.to_vec()).await?;
+            session = cookies.to_vec();
             debug!("Cookies loaded");
         }
-        Ok(Self {
+        let mut tabs = Vec::new();
+        for _ in 0..pool_size.unwrap_or_default() {
+            tabs.push(open_tab(&browser, proxy.as_ref()).await?);
+        }
+        let mut kagi = Self {
             auth_type,
+            external: launch_config.is_none(),
+            _profile: profile,
+            proxy,
+            host,
+            timeouts,
+            pool: None,
+            launch_config,
+            alive: Mutex::new(alive),
+            generation: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            session: Mutex::new(session),
             #[cfg(feature = "tokio-runtime")]
             browser: Arc::new(tokio::sync::RwLock::new(browser)),
             #[cfg(feature = "async-std-runtime")]
             browser: Arc::new(async_std::sync::RwLock::new(browser)),
-        })
+        };
+        if !tabs.is_empty() {
+            debug!("Created page pool with {} tabs", tabs.len());
+            kagi.pool = Some(Arc::new(PagePool::new(tabs)));
+            // The tabs share the default context, so signing in once authenticates all of them
+            if !matches!(kagi.auth_type, AuthType::Icognito) {
+                kagi.sign_in(None).await?;
+            }
+        }
+        Ok(kagi)
     }
 
     /// Close the browser instance
+    ///
+    /// A browser attached with [`Kagi::connect`] is left running, only the tabs of the page pool
+    /// are closed.
     pub async fn close(&self) -> Result<(), Error> {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(pool) = &self.pool {
+            pool.close().await;
+        }
+        if self.external {
+            debug!("Detaching from external browser");
+            return Ok(());
+        }
         let mut browser = self.browser.write().await;
         browser.close().await?;
         browser.wait().await?;
         Ok(())
     }
 
+    /// Whether the handler task still receives events from the browser
+    fn is_alive(&self) -> bool {
+        self.alive
+            .lock()
+            .map(|alive| alive.load(Ordering::SeqCst))
+            .unwrap_or(false)
+    }
+
+    /// Relaunch the browser if it exited, returning whether it was relaunched.
+    ///
+    /// The new browser uses the same configuration and starts with the cookies of the last
+    /// successful request. Browsers attached with [`Kagi::connect`] or closed with
+    /// [`Kagi::close`] are never relaunched.
+    async fn recover(&self) -> Result<bool, Error> {
+        let Some(config) = &self.launch_config else {
+            return Ok(false);
+        };
+        if self.closed.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        let mut browser = self.browser.write().await;
+        // The handler may not have noticed the exit yet, so also check the process
+        let exited = matches!(browser.try_wait(), Ok(Some(_)));
+        if self.is_alive() && !exited {
+            return Ok(false);
+        }
+        warn!("Browser exited, relaunching");
+        let (relaunched, handler) = Browser::launch(config.clone()).await?;
+        if let Ok(mut alive) = self.alive.lock() {
+            *alive = drive(handler);
+        }
+        let cookies = self
+            .session
+            .lock()
+            .map(|session| session.clone())
+            .unwrap_or_default();
+        if !cookies.is_empty() {
+            relaunched.set_cookies(cookies).await?;
+            debug!("Session cookies restored");
+        }
+        *browser = relaunched;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        Ok(true)
+    }
+
+    /// Remember the cookies of the instance to restore them after a relaunch
+    async fn save_session(&self) -> Result<(), Error> {
+        if let AuthType::Icognito = self.auth_type {
+            return Ok(());
+        }
+        let cookies = self.cookies().await?;
+        if let Ok(mut session) = self.session.lock() {
+            *session = cookies.into_iter().map(cookie_param).collect();
+        }
+        Ok(())
+    }
+
     /// Get the cookies stored in the browser context
     pub async fn cookies(&self) -> Result<Vec<Cookie>, Error> {
         let cookies = self.browser.read().await.get_cookies().await?;
         Ok(cookies)
     }
 
-    /// Initialize a new page with optional incognito mode
-    async fn init_page(&self, auth_type: &AuthType) -> Result<Page, Error> {
+    /// Sign in without performing a search, e.g. to populate [`Kagi::cookies`].
+    ///
+    /// Uses `auth_type` if given, otherwise the authentication type of this instance.
+    pub async fn sign_in(&self, auth_type: Option<AuthType>) -> Result<(), Error> {
+        let url = self.url("settings")?;
+        self.open_page(url, auth_type, &SearchOptions::default())
+            .await?;
+        Ok(())
+    }
+
+    /// Initialize a new page with optional incognito mode.
+    ///
+    /// A `proxy` override gets a dedicated browser context routed through it. Unless the page is
+    /// incognito, the context starts with the cookies of the instance so the session carries over.
+    ///
+    /// With a page pool, this waits for an idle tab. Pages in the default context reuse the tab,
+    /// pages needing their own context are created fresh but still occupy its slot.
+    async fn init_page(&self, auth_type: &AuthType, proxy: Option<&Proxy>) -> Result<Page, Error> {
+        let mut lease = match &self.pool {
+            Some(pool) => Some(pool.acquire().await?),
+            None => None,
+        };
+        let incognito = matches!(auth_type, AuthType::Icognito);
+        if !incognito
+            && proxy.is_none()
+            && let Some(mut lease) = lease.take()
+        {
+            let generation = self.generation.load(Ordering::SeqCst);
+            if lease.generation() != generation {
+                debug!("Replacing tab of a previous browser");
+                let browser = self.browser.read().await;
+                lease.replace(open_tab(&browser, self.proxy.as_ref()).await?, generation);
+            }
+            let page = Page::new(lease.tab().clone(), None, self.browser.clone());
+            return Ok(page.with_lease(lease));
+        }
         let browser = self.browser.read().await;
-        let context_id = if let AuthType::Icognito = auth_type {
-            debug!("Creating incognito browser context");
-            Some(
-                browser
-                    .create_browser_context(CreateBrowserContextParams::default())
-                    .await?,
-            )
+        let context_id = if incognito || proxy.is_some() {
+            debug!("Creating browser context");
+            let mut params = CreateBrowserContextParams::builder();
+            if let Some(proxy) = proxy {
+                debug!("Routing browser context through proxy {}", proxy.server());
+                params = params.proxy_server(proxy.server());
+            }
+            Some(browser.create_browser_context(params.build()).await?)
         } else {
             None
         };
@@ -173,10 +409,29 @@ impl Kagi {
         if let Some(context_id) = &context_id {
             builder = builder.browser_context_id(context_id.clone());
         }
-        let param = builder.build().map_err(|e| browser_error!("{}", e))?;
+        let param = builder.build().map_err(Error::InvalidConfig)?;
         let page = browser.new_page(param).await?;
+        if proxy.is_some() && !incognito {
+            let cookies = browser
+                .get_cookies()
+                .await?
+                .into_iter()
+                .map(cookie_param)
+                .collect::<Vec<_>>();
+            if !cookies.is_empty() {
+                page.execute(SetCookiesParams::new(cookies)).await?;
+                debug!("Cookies copied to proxy browser context");
+            }
+        }
         let _ = browser;
-        Ok(Page::new(page, context_id, self.browser.clone()))
+        let mut page = Page::new(page, context_id, self.browser.clone());
+        if let Some(credentials) = proxy.or(self.proxy.as_ref()).and_then(Proxy::credentials) {
+            page.inner().authenticate(credentials).await?;
+        }
+        if let Some(lease) = lease {
+            page = page.with_lease(lease);
+        }
+        Ok(page)
     }
 
     /// Performs a search query on Kagi and returns the results.
@@ -185,14 +440,19 @@ impl Kagi {
     /// 1. Create a new page
     /// 2. Navigate to Kagi search
     /// 3. Handle authentication if needed
-    /// 4. Extract search results
+    /// 4. Extract search results, loading more batches until `limit` is satisfied
+    ///
+    /// Use [`Kagi::search_response`] to also get the rest of the search page, such as the Quick
+    /// Answer, related searches or the infobox.
     ///
     /// # Parameters
     ///
     /// - `query`: The search term to look for
     /// - `limit`: Maximum number of results to return
+    /// - `options`: Filters and sorting applied to the search, see [`SearchOptions`]
     /// - `auth_type`: Optional authentication type to use for this search performing in incognito
-    ///     mode. Only `AuthType::Token` and `AuthType::Login` are supported.
+    ///   mode. `AuthType::Cookies` and `AuthType::Icognito` cannot sign in, so they are not
+    ///   supported.
     ///
     /// # Returns
     ///
@@ -200,15 +460,16 @@ impl Kagi {
     /// Returns `Ok(None)` if no results were found.
     /// Returns `Err` if any error occurred during the search process.
     ///
-    /// Each `SearchResult` contains:
+    /// Each `SearchResult` contains, among others:
+    /// - `rank`: The 1-based position of the result
     /// - `title`: The title of the search result
     /// - `url`: The URL of the result
     /// - `snippet`: A brief description or snippet from the result
     ///
     /// # Examples
     ///
-    /// ```rust
-    /// use kagisearch::{Kagi, AuthType};
+    /// ```rust,no_run
+    /// use kagisearch::{Kagi, AuthType, SearchOptions};
     ///
     /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
     /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
@@ -220,7 +481,9 @@ impl Kagi {
     ///     let mut kagi = Kagi::new(AuthType::Token(token)).await?;
     ///     
     ///     // Search for "Rust programming" and get up to 5 results
-    ///     let results = kagi.search("Rust programming", 5, None).await?;
+    ///     let results = kagi
+    ///         .search("Rust programming", 5, &SearchOptions::default(), None)
+    ///         .await?;
     ///     
     ///     if let Some(results) = results {
     ///         for result in results {
@@ -248,9 +511,279 @@ impl Kagi {
         &self,
         query: &str,
         limit: usize,
+        options: &SearchOptions,
         auth_type: Option<AuthType>,
     ) -> Result<Option<Vec<SearchResult>>, Error> {
-        let page = self.init_page(&self.auth_type).await?;
+        // Skip the Quick Answer and the other sections, which would only be thrown away
+        let response = self
+            .search_page(query, limit, options, auth_type, false)
+            .await?;
+        if response.results.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(response.results))
+    }
+
+    /// Performs a search query on Kagi and yields each result as soon as it is extracted.
+    ///
+    /// This behaves like [`Kagi::search`], but nothing happens until the stream is polled, and
+    /// results are yielded one by one, including those from subsequent batches. The stream ends
+    /// once `limit` results were yielded or no more results are available, and stops after the
+    /// first error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use futures::TryStreamExt;
+    /// use kagisearch::{Kagi, AuthType, SearchOptions};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let token = std::env::var("KAGI_TOKEN")?;
+    ///     let kagi = Kagi::new(AuthType::Token(token)).await?;
+    ///
+    ///     let options = SearchOptions::default();
+    ///     let mut results = std::pin::pin!(kagi.search_stream("Rust programming", 20, &options, None));
+    ///     while let Some(result) = results.try_next().await? {
+    ///         println!("{}: {}", result.title, result.url);
+    ///     }
+    ///
+    ///     kagi.close().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn search_stream<'a>(
+        &'a self,
+        query: &'a str,
+        limit: usize,
+        options: &'a SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> impl Stream<Item = Result<SearchResult, Error>> + 'a {
+        let state = StreamState::Pending(auth_type);
+        stream::try_unfold(state, move |state| async move {
+            let (mut cursor, deadline) = match state {
+                StreamState::Pending(auth_type) => {
+                    let deadline = deadline(&self.timeouts(options));
+                    let open = self.open_results(query, limit, options, auth_type);
+                    (until(deadline, open).await?, deadline)
+                }
+                StreamState::Running(cursor, deadline) => (cursor, deadline),
+            };
+            let result = until(deadline, cursor.next()).await?;
+            Ok(result.map(|result| (result, StreamState::Running(cursor, deadline))))
+        })
+    }
+
+    /// Performs several search queries on Kagi, running up to `concurrency` of them at once.
+    ///
+    /// Each query behaves like [`Kagi::search`] with the authentication of the instance. The
+    /// results are returned in the order of `queries`, each paired with its query, and a failed
+    /// query does not affect the others. Combine with [`KagiBuilder::pool_size`] to reuse the
+    /// same tabs for the whole batch.
+    pub async fn search_many<I, S>(
+        &self,
+        queries: I,
+        limit: usize,
+        options: &SearchOptions,
+        concurrency: usize,
+    ) -> Vec<(String, Result<Option<Vec<SearchResult>>, Error>)>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        stream::iter(queries.into_iter().map(Into::into))
+            .map(|query: String| async move {
+                debug!("Searching {:?}", query);
+                let results = self.search(&query, limit, options, None).await;
+                (query, results)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Performs a search query on Kagi and returns the results along with the rest of the page.
+    ///
+    /// Parameters follow [`Kagi::search`]. Besides the results, the [`SearchResponse`] contains
+    /// the Quick Answer, related searches, the infobox, grouped sections such as "Interesting
+    /// finds", the corrected query and the elapsed time. When the query ends with `?`, Kagi
+    /// generates a Quick Answer, and this method waits for the streamed answer to complete before
+    /// returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Page initialization fails
+    /// - Navigation to search page fails
+    /// - Authentication fails
+    /// - Result extraction fails
+    /// - A phase exceeds its timeout, see [`Timeouts`]
+    ///
+    pub async fn search_response(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<SearchResponse, Error> {
+        self.search_page(query, limit, options, auth_type, true)
+            .await
+    }
+
+    /// Search and extract the results, and the other sections of the page if `sections` is set
+    async fn search_page(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+        sections: bool,
+    ) -> Result<SearchResponse, Error> {
+        let search = async {
+            let generation = self.generation.load(Ordering::SeqCst);
+            let result = self
+                .try_search_response(query, limit, options, auth_type.clone(), sections)
+                .await;
+            let Err(e) = &result else {
+                return result;
+            };
+            if !e.is_connection_lost() {
+                return result;
+            }
+            let relaunched = match self.recover().await {
+                Ok(relaunched) => relaunched,
+                Err(relaunch) => {
+                    warn!("Failed to relaunch the browser: {}", relaunch);
+                    return result;
+                }
+            };
+            // Another request may have relaunched the browser already
+            if relaunched || self.generation.load(Ordering::SeqCst) != generation {
+                warn!("Retrying search after browser crash: {}", e);
+                return self
+                    .try_search_response(query, limit, options, auth_type, sections)
+                    .await;
+            }
+            result
+        };
+        until(deadline(&self.timeouts(options)), search).await
+    }
+
+    /// Single attempt of [`Kagi::search_page`]
+    async fn try_search_response(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+        sections: bool,
+    ) -> Result<SearchResponse, Error> {
+        let started = Instant::now();
+        let mut cursor = self.open_results(query, limit, options, auth_type).await?;
+        let (quick_answer, mut response) = if sections {
+            let expect_answer = query.trim_end().ends_with('?');
+            let quick_answer = extract_quick_answer(
+                &cursor.page,
+                expect_answer,
+                self.timeouts(options).quick_answer,
+            )
+            .await?;
+            (quick_answer, extract_sections(&cursor.page).await?)
+        } else {
+            (None, SearchResponse::default())
+        };
+        while let Some(result) = cursor.next().await? {
+            response.results.push(result);
+        }
+        response.quick_answer = quick_answer;
+        response.elapsed = started.elapsed();
+        if let Err(e) = self.save_session().await {
+            warn!("Failed to save the session cookies: {}", e);
+        }
+        Ok(response)
+    }
+
+    /// Open the search page and wait for the first batch of results
+    async fn open_results(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<ResultCursor, Error> {
+        let mut url = self.url("search")?;
+        url.query_pairs_mut()
+            .append_pair("q", query)
+            .extend_pairs(options.query_pairs());
+        let page = self.open_page(url, auth_type, options).await?;
+        let results_timeout = self.timeouts(options).results;
+
+        let deadline = Instant::now() + results_timeout;
+        let mut rendered = false;
+        let search_results = loop {
+            if page.inner().find_element(".results-box").await.is_ok() {
+                debug!("Results found");
+                rendered = true;
+                // Count across all results boxes, like the batches loaded later
+                let search_results = page.inner().find_elements(SEARCH_RESULT_SELECTOR).await?;
+                if !search_results.is_empty() {
+                    debug!("Search results found");
+                    break search_results;
+                }
+            }
+            if Instant::now() >= deadline {
+                if !rendered {
+                    return Err(Error::Timeout {
+                        phase: Phase::Results,
+                    });
+                }
+                debug!("No search results found");
+                break Vec::new();
+            }
+            debug!("No search results found, waiting");
+            // Sometimes the results take a while to load
+            Delay::new(RETRY_TIMEOUT).await;
+        };
+
+        Ok(ResultCursor {
+            page,
+            elements: search_results,
+            index: 0,
+            yielded: 0,
+            limit,
+            results_timeout,
+        })
+    }
+
+    /// URL of `path` relative to the Kagi host
+    pub(crate) fn url(&self, path: &str) -> Result<Url, url::ParseError> {
+        self.host.join(path)
+    }
+
+    /// Time limits of a request made with `options`
+    pub(crate) fn timeouts(&self, options: &SearchOptions) -> Timeouts {
+        options.timeouts.unwrap_or(self.timeouts)
+    }
+
+    /// Open a new page at `url`, signing in with `auth_type` or the instance authentication.
+    ///
+    /// The proxy and timeouts of `options` override those of the instance.
+    pub(crate) async fn open_page(
+        &self,
+        url: Url,
+        auth_type: Option<AuthType>,
+        options: &SearchOptions,
+    ) -> Result<Page, Error> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(Error::BrowserClosed);
+        }
+        if !self.is_alive() && !self.recover().await? {
+            return Err(Error::BrowserClosed);
+        }
+        let page = self
+            .init_page(&self.auth_type, options.proxy.as_ref())
+            .await?;
 
         let auth_type = if let Some(auth_type) = &auth_type {
             auth_type
@@ -258,95 +791,292 @@ impl Kagi {
             &self.auth_type
         };
 
+        self.navigate(&page, url, auth_type, &self.timeouts(options))
+            .await?;
+        Ok(page)
+    }
+
+    /// Navigate to `url`, signing in first if Kagi redirects to the sign in page
+    async fn navigate(
+        &self,
+        page: &Page,
+        url: Url,
+        auth_type: &AuthType,
+        timeouts: &Timeouts,
+    ) -> Result<(), Error> {
+        let mut signed_in = false;
         loop {
-            let url = Url::parse_with_params(&format!("{}/search", HOST), &[("q", query)])?;
-            page.inner().goto(url).await?.wait_for_navigation().await?;
-            let Some(url) = page.inner().url().await? else {
-                return Err(browser_error!("Failed to get URL"));
+            timeout(Phase::Navigation, timeouts.navigation, async {
+                page.inner()
+                    .goto(url.clone())
+                    .await?
+                    .wait_for_navigation()
+                    .await?;
+                Ok(())
+            })
+            .await?;
+            let Some(current) = page.inner().url().await? else {
+                return Err(Error::NavigationFailed {
+                    url: url.to_string(),
+                });
             };
-            let url = Url::parse(&url)?;
-            if url.path() == "/signin" {
+            let current = Url::parse(&current)?;
+            if current.path() == self.url("signin")?.path() {
+                // Signing in again would loop forever if the session does not stick
+                if signed_in {
+                    warn!("Still redirected to sign in after signing in");
+                    return Err(Error::NavigationFailed {
+                        url: url.to_string(),
+                    });
+                }
                 debug!("Sign in required");
                 match auth_type {
                     AuthType::Login(email, password, code) => {
-                        handle_signin(page.inner(), email, password, code.as_deref()).await?;
+                        let two_factor = TwoFactor::Code(code.as_ref());
+                        handle_signin(
+                            page.inner(),
+                            &self.host,
+                            email,
+                            password,
+                            two_factor,
+                            timeouts.sign_in,
+                        )
+                        .await?;
+                    }
+                    AuthType::LoginTotp(email, password, secret) => {
+                        let two_factor = TwoFactor::Totp(secret);
+                        handle_signin(
+                            page.inner(),
+                            &self.host,
+                            email,
+                            password,
+                            two_factor,
+                            timeouts.sign_in,
+                        )
+                        .await?;
                     }
                     AuthType::Token(token) => {
-                        handle_token(page.inner(), token).await?;
+                        let signin = handle_token(page.inner(), &self.host, token);
+                        timeout(Phase::SignIn, timeouts.sign_in, signin).await?;
+                    }
+                    AuthType::TokenFrom(provider) => {
+                        let token = provider.secret().await?;
+                        let signin = handle_token(page.inner(), &self.host, &token);
+                        timeout(Phase::SignIn, timeouts.sign_in, signin).await?;
+                    }
+                    AuthType::LoginFrom(email, provider, code) => {
+                        let password = provider.secret().await?;
+                        let two_factor = TwoFactor::Code(code.as_ref());
+                        handle_signin(
+                            page.inner(),
+                            &self.host,
+                            email,
+                            &password,
+                            two_factor,
+                            timeouts.sign_in,
+                        )
+                        .await?;
                     }
                     AuthType::Cookies(_) => {
-                        return Err(auth_error!("Invalid cookies"));
+                        return Err(Error::InvalidCookies);
                     }
                     AuthType::Icognito => {
-                        return Err(auth_error!("Incognito mode not supported"));
+                        return Err(Error::SignInRequired);
                     }
                 }
+                signed_in = true;
                 continue;
             }
-            if url.path() != "/search" {
-                return Err(browser_error!("Failed to navigate to search page"));
+            if current.path() != url.path() {
+                return Err(Error::NavigationFailed {
+                    url: url.to_string(),
+                });
             }
             debug!("Already signed in");
-            break;
+            return Ok(());
         }
+    }
+}
 
-        let search_results = async {
-            for _ in 0..MAX_RETRIES {
-                let results = page.inner().find_element(".results-box").await?;
-                debug!("Results found");
-                let search_results = results.find_elements(".search-result").await?;
-                debug!("Search results found");
-                if search_results.is_empty() {
-                    debug!("No search results found, waiting");
-                    // Sometimes the results take a while to load
-                    Delay::new(RETRY_TIMEOUT).await;
-                    continue;
+/// State of a [`Kagi::search_stream`]
+enum StreamState {
+    /// The search page has not been opened yet
+    Pending(Option<AuthType>),
+    /// Results are being extracted, until the overall deadline if any
+    Running(ResultCursor, Option<Instant>),
+}
+
+/// Walks the result elements of an open search page, loading more batches on demand
+struct ResultCursor {
+    page: Page,
+    elements: Vec<Element>,
+    index: usize,
+    yielded: usize,
+    limit: usize,
+    /// Time to wait for a further batch to load
+    results_timeout: Duration,
+}
+
+impl ResultCursor {
+    /// Extract the next complete search result
+    async fn next(&mut self) -> Result<Option<SearchResult>, Error> {
+        while self.yielded < self.limit {
+            if self.index >= self.elements.len() {
+                let more =
+                    load_more_results(self.page.inner(), self.index, self.results_timeout).await?;
+                let Some(more) = more else {
+                    debug!("No more results button, all results loaded");
+                    return Ok(None);
+                };
+                self.elements = more;
+                continue;
+            }
+            let element = &self.elements[self.index];
+            self.index += 1;
+            if let Some(result) = extract_result(element, self.yielded + 1).await? {
+                self.yielded += 1;
+                return Ok(Some(result));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Drive the browser events in the background, returning a flag cleared once the browser exits
+fn drive(mut handler: Handler) -> Arc<AtomicBool> {
+    let alive = Arc::new(AtomicBool::new(true));
+    let flag = alive.clone();
+    spawn(async move {
+        while let Some(h) = handler.next().await {
+            match h {
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("Browser handler error: {}", e);
+                    if e.to_string().contains("Browser closed") {
+                        break;
+                    }
                 }
-                return Ok(Some(search_results));
             }
-            Ok::<Option<Vec<Element>>, Error>(None)
         }
-        .await?;
+        debug!("Browser handler stopped");
+        flag.store(false, Ordering::SeqCst);
+    });
+    alive
+}
 
-        let Some(search_results) = search_results else {
-            return Ok(None);
+/// Open a blank tab for the page pool, answering authentication challenges of `proxy`
+async fn open_tab(browser: &Browser, proxy: Option<&Proxy>) -> Result<chromiumoxide::Page, Error> {
+    let tab = browser.new_page("about:blank").await?;
+    if let Some(credentials) = proxy.and_then(Proxy::credentials) {
+        tab.authenticate(credentials).await?;
+    }
+    Ok(tab)
+}
+
+/// Convert a cookie read from the browser into one that can be set in another context
+fn cookie_param(cookie: Cookie) -> CookieParam {
+    let mut param = CookieParam::new(cookie.name, cookie.value);
+    param.domain = Some(cookie.domain);
+    param.path = Some(cookie.path);
+    param.secure = Some(cookie.secure);
+    param.http_only = Some(cookie.http_only);
+    param.same_site = cookie.same_site;
+    if !cookie.session {
+        param.expires = Some(TimeSinceEpoch::new(cookie.expires));
+    }
+    param
+}
+
+/// Extract a single search result, returning `None` if the element is incomplete
+async fn extract_result(result: &Element, rank: usize) -> Result<Option<SearchResult>, Error> {
+    let Ok(title) = result.find_element(".__sri-title").await else {
+        debug!("Title class not found");
+        return Ok(None);
+    };
+    let Some(title) = title.inner_text().await? else {
+        debug!("Title class not found");
+        return Ok(None);
+    };
+    let Ok(url) = result.find_element(".__sri-url-box").await else {
+        debug!("URL class not found");
+        return Ok(None);
+    };
+    let Some(url) = url.find_element("a").await?.attribute("href").await? else {
+        debug!("URL attribute not found");
+        return Ok(None);
+    };
+    let Ok(snippet) = result.find_element(".__sri-desc").await else {
+        debug!("Description class not found");
+        return Ok(None);
+    };
+    let Some(snippet) = snippet.inner_text().await? else {
+        debug!("Description class not found");
+        return Ok(None);
+    };
+    let domain = Url::parse(&url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()));
+    let mut sitelinks = Vec::new();
+    for link in result.find_elements(SITELINK_SELECTOR).await? {
+        let (Some(title), Some(url)) = (link.inner_text().await?, link.attribute("href").await?)
+        else {
+            continue;
         };
+        sitelinks.push(Link {
+            title: title.trim().to_string(),
+            url,
+        });
+    }
+    let grouped = result
+        .call_js_fn(GROUPED_FN, false)
+        .await?
+        .result
+        .value
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    Ok(Some(SearchResult {
+        rank,
+        title,
+        url,
+        domain,
+        snippet,
+        published: find_text(result, ".__sri-time").await?,
+        favicon: find_attribute(result, ".__sri-url-box img", "src").await?,
+        sitelinks,
+        grouped,
+    }))
+}
 
-        let mut results = Vec::new();
-        for result in &search_results {
-            if results.len() >= limit {
-                break;
-            }
-            let Ok(title) = result.find_element(".__sri-title").await else {
-                debug!("Title class not found");
-                continue;
-            };
-            let Some(title) = title.inner_text().await? else {
-                debug!("Title class not found");
-                continue;
-            };
-            let Ok(url) = result.find_element(".__sri-url-box").await else {
-                debug!("URL class not found");
-                continue;
-            };
-            let Some(url) = url.find_element("a").await?.attribute("href").await? else {
-                debug!("URL attribute not found");
-                continue;
-            };
-            let Ok(snippet) = result.find_element(".__sri-desc").await else {
-                debug!("Description class not found");
-                continue;
-            };
-            let Some(snippet) = snippet.inner_text().await? else {
-                debug!("Description class not found");
-                continue;
-            };
-            results.push(SearchResult {
-                title,
-                url,
-                snippet,
+/// Click the "More results" button and wait for the next batch to be appended.
+///
+/// Returns all result elements on the page once more than `loaded` are present, or `None` if
+/// there is no button, i.e. no further batch. Fails with [`Error::Timeout`] if the clicked batch
+/// does not arrive within `timeout`.
+async fn load_more_results(
+    page: &chromiumoxide::Page,
+    loaded: usize,
+    timeout: Duration,
+) -> Result<Option<Vec<Element>>, Error> {
+    let button = match page.find_element(MORE_RESULTS_SELECTOR).await {
+        Ok(button) => button,
+        Err(e) if is_missing(&e) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    debug!("Loading more results");
+    button.scroll_into_view().await?.click().await?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        let search_results = page.find_elements(SEARCH_RESULT_SELECTOR).await?;
+        if search_results.len() > loaded {
+            return Ok(Some(search_results));
+        }
+        if Instant::now() >= deadline {
+            // The button was there, so a batch was expected rather than the end of the results
+            return Err(Error::Timeout {
+                phase: Phase::Results,
             });
         }
-        Ok(Some(results))
+        debug!("Next batch not loaded yet, waiting");
+        Delay::new(RETRY_TIMEOUT).await;
     }
 }
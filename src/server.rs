@@ -0,0 +1,232 @@
+//! HTTP server compatible with the Kagi Search API.
+//!
+//! Exposes `GET /api/v0/search` using the same request parameters, `Authorization: Bot <key>`
+//! header and JSON response format as the [official API](https://help.kagi.com/kagi/api/search.html),
+//! backed by a shared [`Kagi`] instance.
+
+use std::{
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    Json, Router,
+    extract::{Query, State, rejection::QueryRejection},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{Error, Kagi, SearchOptions, SearchResponse};
+
+const DEFAULT_LIMIT: usize = 10;
+/// Upper bound of `limit`, each batch of results past the first costs a click on the page
+const MAX_LIMIT: usize = 100;
+const NODE: &str = "kagisearch";
+
+struct ServerState {
+    kagi: Arc<Kagi>,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct Meta {
+    id: String,
+    node: &'static str,
+    ms: u128,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Data {
+    Result {
+        t: u8,
+        rank: usize,
+        url: String,
+        title: String,
+        snippet: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        published: Option<String>,
+    },
+    Related {
+        t: u8,
+        list: Vec<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    code: u16,
+    msg: String,
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ApiResponse {
+    meta: Meta,
+    data: Option<Vec<Data>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Vec<ApiError>>,
+}
+
+/// Build the router serving the Kagi Search API.
+///
+/// When `api_key` is set, requests must carry an `Authorization: Bot <api_key>` header.
+pub fn router(kagi: Arc<Kagi>, api_key: Option<String>) -> Router {
+    Router::new()
+        .route("/api/v0/search", get(search))
+        .with_state(Arc::new(ServerState { kagi, api_key }))
+}
+
+async fn search(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    params: Result<Query<SearchParams>, QueryRejection>,
+) -> Response {
+    let started = Instant::now();
+    let meta = || Meta {
+        id: request_id(),
+        node: NODE,
+        ms: started.elapsed().as_millis(),
+    };
+
+    if let Some(api_key) = &state.api_key {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bot "));
+        if token != Some(api_key.as_str()) {
+            return error_response(StatusCode::UNAUTHORIZED, meta(), "Unauthorized");
+        }
+    }
+    let params = match params {
+        Ok(Query(params)) => params,
+        Err(rejection) => {
+            return error_response(StatusCode::BAD_REQUEST, meta(), &rejection.body_text());
+        }
+    };
+    if params.q.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, meta(), "Missing query");
+    }
+
+    let limit = clamp_limit(params.limit);
+    debug!("Serving search for {:?} with limit {}", params.q, limit);
+    match state
+        .kagi
+        .search_response(&params.q, limit, &SearchOptions::default(), None)
+        .await
+    {
+        Ok(response) => Json(ApiResponse {
+            meta: meta(),
+            data: Some(into_data(response)),
+            error: None,
+        })
+        .into_response(),
+        Err(e @ Error::Timeout { .. }) => {
+            error_response(StatusCode::GATEWAY_TIMEOUT, meta(), &e.to_string())
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, meta(), &e.to_string()),
+    }
+}
+
+fn clamp_limit(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+}
+
+fn into_data(response: SearchResponse) -> Vec<Data> {
+    let mut data: Vec<Data> = response
+        .results
+        .into_iter()
+        .map(|result| Data::Result {
+            t: 0,
+            rank: result.rank,
+            url: result.url,
+            title: result.title,
+            snippet: result.snippet,
+            published: result.published,
+        })
+        .collect();
+    if !response.related_searches.is_empty() {
+        data.push(Data::Related {
+            t: 1,
+            list: response.related_searches,
+        });
+    }
+    data
+}
+
+fn error_response(status: StatusCode, meta: Meta, msg: &str) -> Response {
+    let body = ApiResponse {
+        meta,
+        data: None,
+        error: Some(vec![ApiError {
+            code: status.as_u16(),
+            msg: msg.to_string(),
+            reference: None,
+        }]),
+    };
+    (status, Json(body)).into_response()
+}
+
+fn request_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}", nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SearchResult;
+
+    #[test]
+    fn test_clamp_limit() {
+        assert_eq!(clamp_limit(None), DEFAULT_LIMIT);
+        assert_eq!(clamp_limit(Some(5)), 5);
+        assert_eq!(clamp_limit(Some(MAX_LIMIT + 1)), MAX_LIMIT);
+    }
+
+    #[test]
+    fn test_into_data() {
+        let response = SearchResponse {
+            results: vec![SearchResult {
+                rank: 1,
+                title: "The Rust Programming Language".to_string(),
+                url: "https://www.rust-lang.org/".to_string(),
+                domain: Some("www.rust-lang.org".to_string()),
+                snippet: "A language empowering everyone".to_string(),
+                published: None,
+                favicon: None,
+                sitelinks: Vec::new(),
+                grouped: false,
+            }],
+            related_searches: vec!["rust tutorial".to_string()],
+            ..Default::default()
+        };
+        let data = serde_json::to_value(into_data(response)).unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!([
+                {
+                    "t": 0,
+                    "rank": 1,
+                    "url": "https://www.rust-lang.org/",
+                    "title": "The Rust Programming Language",
+                    "snippet": "A language empowering everyone",
+                },
+                { "t": 1, "list": ["rust tutorial"] },
+            ])
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    AuthType, Error, Kagi, Phase, SearchOptions,
+    timeout::{deadline, until},
+};
+
+const SUMMARY_SELECTOR: &str = ".summary-output";
+const TEXT_INPUT_SELECTOR: &str = "textarea";
+const SUBMIT_SELECTOR: &str = "button[type='submit']";
+
+/// Kind of summary produced by the Universal Summarizer
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SummaryType {
+    /// Paragraph style summary
+    #[default]
+    Summary,
+    /// Bullet point list of key moments
+    KeyMoments,
+}
+
+impl SummaryType {
+    fn as_param(&self) -> &'static str {
+        match self {
+            SummaryType::Summary => "summary",
+            SummaryType::KeyMoments => "takeaway",
+        }
+    }
+}
+
+/// Output of the Universal Summarizer
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub enum Summary {
+    /// Paragraph style summary
+    Text(String),
+    /// Key moments, one entry per bullet point
+    KeyMoments(Vec<String>),
+}
+
+impl Kagi {
+    /// Summarize a URL or a piece of text with the Kagi Universal Summarizer.
+    ///
+    /// # Parameters
+    ///
+    /// - `input`: A `http(s)` URL to summarize, anything else is summarized as text
+    /// - `summary_type`: Whether to produce a paragraph summary or a list of key moments
+    /// - `target_language`: Optional language code of the summary, e.g. `EN` or `DE`
+    /// - `auth_type`: Optional authentication type to use for this request performing in incognito
+    ///   mode. `AuthType::Cookies` and `AuthType::Icognito` cannot sign in, so they are not
+    ///   supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Page initialization fails
+    /// - Navigation to the summarizer fails
+    /// - Authentication fails
+    /// - The summary does not complete in time
+    ///
+    pub async fn summarize(
+        &self,
+        input: &str,
+        summary_type: SummaryType,
+        target_language: Option<&str>,
+        auth_type: Option<AuthType>,
+    ) -> Result<Summary, Error> {
+        let timeouts = self.timeouts(&SearchOptions::default());
+        until(deadline(&timeouts), async {
+            let target = Url::parse(input)
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"));
+
+            let mut url = self.url("summarizer")?;
+            {
+                let mut pairs = url.query_pairs_mut();
+                if let Some(target) = &target {
+                    pairs.append_pair("url", target.as_str());
+                }
+                pairs.append_pair("summary", summary_type.as_param());
+                if let Some(target_language) = target_language {
+                    pairs.append_pair("target_language", target_language);
+                }
+            }
+            let page = self
+                .open_page(url, auth_type, &SearchOptions::default())
+                .await?;
+
+            if target.is_none() {
+                debug!("Submitting text to summarize");
+                let input_box = page
+                    .inner()
+                    .find_element(TEXT_INPUT_SELECTOR)
+                    .await
+                    .map_err(|e| Error::lookup(TEXT_INPUT_SELECTOR, e))?;
+                input_box.click().await?.type_str(input).await?;
+                page.inner()
+                    .find_element(SUBMIT_SELECTOR)
+                    .await
+                    .map_err(|e| Error::lookup(SUBMIT_SELECTOR, e))?
+                    .click()
+                    .await?;
+            }
+
+            let Some(output) = page
+                .wait_for_stable_element(SUMMARY_SELECTOR, timeouts.summary)
+                .await?
+            else {
+                return Err(Error::Timeout {
+                    phase: Phase::Summary,
+                });
+            };
+            match summary_type {
+                SummaryType::Summary => {
+                    let Some(text) = output.inner_text().await? else {
+                        return Err(Error::selector_missing(SUMMARY_SELECTOR));
+                    };
+                    Ok(Summary::Text(text.trim().to_string()))
+                }
+                SummaryType::KeyMoments => {
+                    let mut moments = Vec::new();
+                    for item in output.find_elements("li").await? {
+                        if let Some(text) = item.inner_text().await? {
+                            moments.push(text.trim().to_string());
+                        }
+                    }
+                    Ok(Summary::KeyMoments(moments))
+                }
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::pin,
+    time::{Duration, Instant},
+};
+
+use futures::future::{Either, select};
+use futures_timer::Delay;
+
+use crate::{
+    Error,
+    consts::{QUICK_ANSWER_TIMEOUT, RESULTS_TIMEOUT, SUMMARY_TIMEOUT},
+};
+
+/// Phase of a request bounded by one of the [`Timeouts`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Loading a page
+    Navigation,
+    /// Signing in with a token or email and password
+    SignIn,
+    /// Waiting for the results to render
+    Results,
+    /// Waiting for a Quick Answer to finish streaming
+    QuickAnswer,
+    /// Waiting for a summary to finish streaming
+    Summary,
+    /// The request as a whole
+    Overall,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Phase::Navigation => "navigation",
+            Phase::SignIn => "sign in",
+            Phase::Results => "results",
+            Phase::QuickAnswer => "quick answer",
+            Phase::Summary => "summary",
+            Phase::Overall => "overall deadline",
+        })
+    }
+}
+
+/// Time limits of the phases of a request, exceeding one fails with [`Error::Timeout`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Loading a page, defaults to 30 seconds
+    pub navigation: Duration,
+    /// Signing in, including the navigations it triggers, defaults to 30 seconds
+    pub sign_in: Duration,
+    /// Waiting for the results to render, defaults to 5 seconds
+    pub results: Duration,
+    /// Waiting for a Quick Answer to finish streaming, defaults to 30 seconds
+    pub quick_answer: Duration,
+    /// Waiting for a summary to finish streaming, defaults to 60 seconds
+    pub summary: Duration,
+    /// Deadline of the whole request, unlimited by default
+    pub overall: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            navigation: Duration::from_secs(30),
+            sign_in: Duration::from_secs(30),
+            results: RESULTS_TIMEOUT,
+            quick_answer: QUICK_ANSWER_TIMEOUT,
+            summary: SUMMARY_TIMEOUT,
+            overall: None,
+        }
+    }
+}
+
+/// Run `future`, failing with [`Error::Timeout`] for `phase` once `duration` elapsed
+pub(crate) async fn timeout<T>(
+    phase: Phase,
+    duration: Duration,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    match select(pin!(future), Delay::new(duration)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(Error::Timeout { phase }),
+    }
+}
+
+/// Deadline of a request with `timeouts` started now
+pub(crate) fn deadline(timeouts: &Timeouts) -> Option<Instant> {
+    timeouts.overall.map(|overall| Instant::now() + overall)
+}
+
+/// Run `future`, failing with [`Error::Timeout`] for [`Phase::Overall`] once `deadline` passed
+pub(crate) async fn until<T>(
+    deadline: Option<Instant>,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    match deadline {
+        Some(deadline) => {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            timeout(Phase::Overall, remaining, future).await
+        }
+        None => future.await,
+    }
+}
@@ -0,0 +1,240 @@
+use chromiumoxide::Element;
+use tracing::debug;
+
+use crate::{
+    AuthType, Error, Kagi, SearchOptions,
+    page::{Page, find_attribute, find_text},
+    timeout::{deadline, until},
+};
+
+/// News search result
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct NewsResult {
+    /// Title of the article
+    pub title: String,
+    /// URL of the article
+    pub url: String,
+    /// Snippet of the article
+    pub snippet: String,
+    /// Publication the article comes from
+    pub source: Option<String>,
+    /// Publish date as displayed by Kagi, e.g. `3 hours ago`
+    pub published: Option<String>,
+}
+
+/// Image search result
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct ImageResult {
+    /// Title of the image
+    pub title: String,
+    /// URL of the full size image
+    pub image_url: String,
+    /// URL of the thumbnail shown by Kagi
+    pub thumbnail: Option<String>,
+    /// URL of the page the image was found on
+    pub source_url: Option<String>,
+    /// Width of the full size image in pixels
+    pub width: Option<u32>,
+    /// Height of the full size image in pixels
+    pub height: Option<u32>,
+}
+
+/// Video search result
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug)]
+pub struct VideoResult {
+    /// Title of the video
+    pub title: String,
+    /// URL of the video
+    pub url: String,
+    /// URL of the thumbnail shown by Kagi
+    pub thumbnail: Option<String>,
+    /// Duration as displayed by Kagi, e.g. `12:34`
+    pub duration: Option<String>,
+    /// Channel or uploader of the video
+    pub channel: Option<String>,
+    /// Publish date as displayed by Kagi
+    pub published: Option<String>,
+}
+
+impl Kagi {
+    /// Performs a search query on Kagi News.
+    ///
+    /// Parameters and return values follow [`Kagi::search`]. Only the results of the first page
+    /// are returned.
+    pub async fn search_news(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<Option<Vec<NewsResult>>, Error> {
+        until(deadline(&self.timeouts(options)), async {
+            let Some((_page, elements)) = self
+                .open_vertical("news", ".newsResultItem", query, options, auth_type)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let mut results = Vec::new();
+            for element in &elements {
+                if results.len() >= limit {
+                    break;
+                }
+                if let Some(result) = extract_news(element).await? {
+                    results.push(result);
+                }
+            }
+            Ok(Some(results))
+        })
+        .await
+    }
+
+    /// Performs a search query on Kagi Images.
+    ///
+    /// Parameters and return values follow [`Kagi::search`]. Only the results of the first page
+    /// are returned.
+    pub async fn search_images(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<Option<Vec<ImageResult>>, Error> {
+        until(deadline(&self.timeouts(options)), async {
+            let Some((_page, elements)) = self
+                .open_vertical("images", ".imageResultItem", query, options, auth_type)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let mut results = Vec::new();
+            for element in &elements {
+                if results.len() >= limit {
+                    break;
+                }
+                if let Some(result) = extract_image(element).await? {
+                    results.push(result);
+                }
+            }
+            Ok(Some(results))
+        })
+        .await
+    }
+
+    /// Performs a search query on Kagi Videos.
+    ///
+    /// Parameters and return values follow [`Kagi::search`]. Only the results of the first page
+    /// are returned.
+    pub async fn search_videos(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<Option<Vec<VideoResult>>, Error> {
+        until(deadline(&self.timeouts(options)), async {
+            let Some((_page, elements)) = self
+                .open_vertical("videos", ".videoResultItem", query, options, auth_type)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let mut results = Vec::new();
+            for element in &elements {
+                if results.len() >= limit {
+                    break;
+                }
+                if let Some(result) = extract_video(element).await? {
+                    results.push(result);
+                }
+            }
+            Ok(Some(results))
+        })
+        .await
+    }
+
+    /// Open the vertical at `/{path}` and wait for the items matching `selector`
+    async fn open_vertical(
+        &self,
+        path: &str,
+        selector: &str,
+        query: &str,
+        options: &SearchOptions,
+        auth_type: Option<AuthType>,
+    ) -> Result<Option<(Page, Vec<Element>)>, Error> {
+        let mut url = self.url(path)?;
+        url.query_pairs_mut()
+            .append_pair("q", query)
+            .extend_pairs(options.query_pairs());
+        let page = self.open_page(url, auth_type, options).await?;
+        let elements = page
+            .wait_for_elements(selector, self.timeouts(options).results)
+            .await?;
+        Ok(elements.map(|elements| (page, elements)))
+    }
+}
+
+async fn extract_news(element: &Element) -> Result<Option<NewsResult>, Error> {
+    let Some(title) = find_text(element, ".newsResultTitle").await? else {
+        debug!("News title not found");
+        return Ok(None);
+    };
+    let Some(url) = find_attribute(element, ".newsResultTitle a, a", "href").await? else {
+        debug!("News URL not found");
+        return Ok(None);
+    };
+    Ok(Some(NewsResult {
+        title,
+        url,
+        snippet: find_text(element, ".newsResultContent")
+            .await?
+            .unwrap_or_default(),
+        source: find_text(element, ".newsResultSource").await?,
+        published: find_text(element, ".newsResultTime").await?,
+    }))
+}
+
+async fn extract_image(element: &Element) -> Result<Option<ImageResult>, Error> {
+    let Some(image_url) = element.attribute("data-image-url").await? else {
+        debug!("Image URL not found");
+        return Ok(None);
+    };
+    let thumbnail = find_attribute(element, "img", "src").await?;
+    let title = match find_attribute(element, "img", "alt").await? {
+        Some(title) => title,
+        None => find_text(element, ".imageResultTitle")
+            .await?
+            .unwrap_or_default(),
+    };
+    let dimension = |value: Option<String>| value.and_then(|value| value.parse().ok());
+    Ok(Some(ImageResult {
+        title,
+        image_url,
+        thumbnail,
+        source_url: find_attribute(element, ".imageResultSource a, a", "href").await?,
+        width: dimension(element.attribute("data-width").await?),
+        height: dimension(element.attribute("data-height").await?),
+    }))
+}
+
+async fn extract_video(element: &Element) -> Result<Option<VideoResult>, Error> {
+    let Some(title) = find_text(element, ".videoResultTitle").await? else {
+        debug!("Video title not found");
+        return Ok(None);
+    };
+    let Some(url) = find_attribute(element, ".videoResultTitle a, a", "href").await? else {
+        debug!("Video URL not found");
+        return Ok(None);
+    };
+    Ok(Some(VideoResult {
+        title,
+        url,
+        thumbnail: find_attribute(element, "img", "src").await?,
+        duration: find_text(element, ".videoResultDuration").await?,
+        channel: find_text(element, ".videoResultAuthor").await?,
+        published: find_text(element, ".videoResultTime").await?,
+    }))
+}
@@ -0,0 +1,206 @@
+//! Local stand-in for Kagi serving the recorded pages in `tests/fixtures`.
+//!
+//! Only the routes used by the sign in and search paths are implemented:
+//! - `GET /search?token=<token>` signs in and redirects to `/`
+//! - `GET /search`, `GET /settings` serve the fixture when signed in, otherwise redirect to
+//!   `/signin`. `GET /search?batch=2` serves the second batch as its own page
+//! - `GET /search_more` serves the second batch appended by the "More results" button, after
+//!   [`BATCH_DELAY`] for [`SLOW_QUERY`]
+//! - `GET /signin` serves the sign in form, `POST /signin` checks the credentials and the
+//!   optional two-factor code, either [`CODE`] or the current code of [`TOTP_SECRET`]
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+
+use totp_rs::{Algorithm, Secret, TOTP};
+
+pub const TOKEN: &str = "test-token";
+pub const EMAIL: &str = "ferris@example.com";
+pub const PASSWORD: &str = "hunter2";
+pub const CODE: &str = "123456";
+pub const TOTP_SECRET: &str = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP";
+pub const SLOW_QUERY: &str = "slow";
+pub const BATCH_DELAY: Duration = Duration::from_secs(3);
+
+const SESSION_COOKIE: &str = "kagi_session=signed-in";
+const PENDING_COOKIE: &str = "kagi_pending=two-factor";
+
+/// Mock server running on a random local port until the test process exits
+pub struct MockKagi {
+    host: String,
+}
+
+impl MockKagi {
+    /// Start a server signing in with [`TOKEN`] or [`EMAIL`] and [`PASSWORD`]
+    pub fn start() -> Self {
+        Self::spawn(false)
+    }
+
+    /// Start a server that also requires a two-factor code after the password
+    pub fn with_two_factor() -> Self {
+        Self::spawn(true)
+    }
+
+    fn spawn(two_factor: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let host = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                thread::spawn(move || handle(stream, two_factor));
+            }
+        });
+        Self { host }
+    }
+
+    /// Base URL to pass to `KagiBuilder::host`
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    cookies: String,
+    form: HashMap<String, String>,
+}
+
+fn handle(stream: TcpStream, two_factor: bool) {
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader) else {
+        return;
+    };
+    let response = route(&request, two_factor);
+    let _ = reader.get_mut().write_all(response.as_bytes());
+}
+
+fn route(request: &Request, two_factor: bool) -> String {
+    let signed_in = request.cookies.contains(SESSION_COOKIE);
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => page(include_str!("../fixtures/home.html")),
+        ("GET", "/search") if request.query.contains_key("token") => {
+            if request.query["token"] == TOKEN {
+                redirect("/", Some(SESSION_COOKIE))
+            } else {
+                redirect("/signin", None)
+            }
+        }
+        ("GET", "/search") if signed_in && request.query.get("batch").is_some_and(|b| b != "1") => {
+            page(&format!(
+                "<html><body><div class=\"results-box\">{}</div></body></html>",
+                include_str!("../fixtures/search_more.html")
+            ))
+        }
+        ("GET", "/search") if signed_in => page(include_str!("../fixtures/search.html")),
+        ("GET", "/search_more") if signed_in => {
+            if request.query.get("q").map(String::as_str) == Some(SLOW_QUERY) {
+                thread::sleep(BATCH_DELAY);
+            }
+            page(include_str!("../fixtures/search_more.html"))
+        }
+        ("GET", "/settings") if signed_in => page(include_str!("../fixtures/settings.html")),
+        ("GET", "/search" | "/search_more" | "/settings") => redirect("/signin", None),
+        ("GET", "/signin") => page(include_str!("../fixtures/signin.html")),
+        ("POST", "/signin") => {
+            if let Some(code) = request.form.get("code") {
+                if request.cookies.contains(PENDING_COOKIE) && valid_code(code) {
+                    return redirect("/search", Some(SESSION_COOKIE));
+                }
+                return page(include_str!("../fixtures/signin_code.html"));
+            }
+            let valid = request.form.get("email").map(String::as_str) == Some(EMAIL)
+                && request.form.get("password").map(String::as_str) == Some(PASSWORD);
+            match (valid, two_factor) {
+                (false, _) => page(include_str!("../fixtures/signin.html")),
+                (true, false) => redirect("/search", Some(SESSION_COOKIE)),
+                (true, true) => with_cookie(
+                    page(include_str!("../fixtures/signin_code.html")),
+                    PENDING_COOKIE,
+                ),
+            }
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    }
+}
+
+fn valid_code(code: &str) -> bool {
+    let secret = Secret::Encoded(TOTP_SECRET.to_string()).to_bytes().unwrap();
+    let totp = TOTP::new_unchecked(Algorithm::SHA1, 6, 1, 30, secret);
+    code == CODE || totp.check_current(code).unwrap_or(false)
+}
+
+fn read_request(reader: &mut BufReader<TcpStream>) -> Option<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut cookies = String::new();
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':')?;
+        match name.to_ascii_lowercase().as_str() {
+            "cookie" => cookies = value.trim().to_string(),
+            "content-length" => length = value.trim().parse().ok()?,
+            _ => {}
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query: parse_pairs(query),
+        cookies,
+        form: parse_pairs(&String::from_utf8_lossy(&body)),
+    })
+}
+
+fn parse_pairs(input: &str) -> HashMap<String, String> {
+    url::form_urlencoded::parse(input.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+fn page(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+fn redirect(location: &str, cookie: Option<&str>) -> String {
+    let response = format!(
+        "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    );
+    match cookie {
+        Some(cookie) => with_cookie(response, cookie),
+        None => response,
+    }
+}
+
+fn with_cookie(response: String, cookie: &str) -> String {
+    response.replacen(
+        "\r\n",
+        &format!("\r\nSet-Cookie: {}; Path=/\r\n", cookie),
+        1,
+    )
+}
@@ -0,0 +1,340 @@
+//! Sign in and extraction tests against the local mock server, runnable without network access
+//! or a Kagi account. A Chrome or Chromium installation is still required.
+
+mod common;
+
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use common::{BATCH_DELAY, CODE, EMAIL, MockKagi, PASSWORD, SLOW_QUERY, TOKEN, TOTP_SECRET};
+use kagisearch::{
+    AuthType, CommandCredential, CredentialProvider, EnvCredential, Error, FileCredential, Kagi,
+    Phase, SearchOptions, Timeouts, TwoFactorCode,
+};
+
+static PROFILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Profile directory of a test browser, deleted when dropped
+struct Profile(PathBuf);
+
+impl Drop for Profile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Launch a browser against `server`. Each browser gets its own profile directory, since
+/// browsers sharing the default one cannot run side by side. Keep the returned [`Profile`] until
+/// the browser is closed.
+async fn launch(server: &MockKagi, auth_type: AuthType) -> Result<(Kagi, Profile), Error> {
+    let profile = Profile(std::env::temp_dir().join(format!(
+        "kagisearch-test-{}-{}",
+        std::process::id(),
+        PROFILES.fetch_add(1, Ordering::SeqCst)
+    )));
+    let kagi = Kagi::builder()
+        .host(server.host())
+        .auth_type(auth_type)
+        .user_data_dir(&profile.0)
+        .build()
+        .await?;
+    Ok((kagi, profile))
+}
+
+fn login(password: &str, code: Option<&str>) -> AuthType {
+    AuthType::Login(
+        EMAIL.to_string(),
+        password.to_string(),
+        code.map(TwoFactorCode::from),
+    )
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_token_search() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, AuthType::Token(TOKEN.to_string())).await?;
+
+    let response = kagi
+        .search_response("rust", 10, &SearchOptions::default(), None)
+        .await?;
+    kagi.close().await?;
+
+    // The first batch holds three results, the rest come from "More results"
+    assert_eq!(response.results.len(), 5);
+    let first = &response.results[0];
+    assert_eq!(first.rank, 1);
+    assert_eq!(first.title, "The Rust Programming Language");
+    assert_eq!(first.url, "https://www.rust-lang.org/");
+    assert_eq!(first.domain.as_deref(), Some("www.rust-lang.org"));
+    assert_eq!(first.sitelinks.len(), 2);
+    assert!(first.favicon.is_some());
+    assert!(!first.grouped);
+    assert_eq!(response.results[1].published.as_deref(), Some("3 days ago"));
+    assert!(response.results[2].grouped);
+    assert_eq!(response.results[3].rank, 4);
+    assert_eq!(response.results[3].title, "Rustlings");
+    assert_eq!(response.related_searches, ["rust tutorial", "rust vs go"]);
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_page() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, AuthType::Token(TOKEN.to_string())).await?;
+
+    let options = SearchOptions {
+        page: Some(2),
+        ..Default::default()
+    };
+    let results = kagi.search("rust", 10, &options, None).await?;
+    kagi.close().await?;
+
+    let results = results.unwrap_or_default();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].rank, 1);
+    assert_eq!(results[0].title, "Rustlings");
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_list_lenses() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, AuthType::Token(TOKEN.to_string())).await?;
+
+    let lenses = kagi.list_lenses(None).await?;
+    kagi.close().await?;
+
+    let lenses: Vec<_> = lenses
+        .iter()
+        .map(|lens| (lens.id.as_str(), lens.name.as_str(), lens.enabled))
+        .collect();
+    assert_eq!(
+        lenses,
+        [
+            ("1", "Forums", Some(true)),
+            ("2", "Programming", Some(false)),
+            ("42", "Rust crates", None),
+        ]
+    );
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_slow_batch() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, AuthType::Token(TOKEN.to_string())).await?;
+
+    let options = SearchOptions {
+        timeouts: Some(Timeouts {
+            results: BATCH_DELAY / 3,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    // The first batch fits the limit, so the slow one is never requested
+    let first = kagi.search(SLOW_QUERY, 3, &options, None).await;
+    let more = kagi.search(SLOW_QUERY, 10, &options, None).await;
+    kagi.close().await?;
+
+    assert_eq!(first?.map(|results| results.len()), Some(3));
+    assert!(matches!(
+        more,
+        Err(Error::Timeout {
+            phase: Phase::Results
+        })
+    ));
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_invalid_token() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, AuthType::Token("expired".to_string())).await?;
+
+    let result = kagi
+        .search("rust", 10, &SearchOptions::default(), None)
+        .await;
+    kagi.close().await?;
+
+    assert!(matches!(result, Err(Error::InvalidToken)));
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_login() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, login(PASSWORD, None)).await?;
+
+    let results = kagi
+        .search("rust", 2, &SearchOptions::default(), None)
+        .await?;
+    kagi.close().await?;
+
+    assert_eq!(results.map(|results| results.len()), Some(2));
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_login_rejected() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, login("wrong password", None)).await?;
+
+    let result = kagi.sign_in(None).await;
+    kagi.close().await?;
+
+    assert!(matches!(result, Err(Error::LoginRejected)));
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_two_factor() -> anyhow::Result<()> {
+    let server = MockKagi::with_two_factor();
+
+    let (kagi, _profile) = launch(&server, login(PASSWORD, None)).await?;
+    let missing = kagi.sign_in(None).await;
+    kagi.close().await?;
+    assert!(matches!(missing, Err(Error::TwoFactorRequired)));
+
+    let (kagi, _profile) = launch(&server, login(PASSWORD, Some("000000"))).await?;
+    let wrong = kagi.sign_in(None).await;
+    kagi.close().await?;
+    assert!(matches!(wrong, Err(Error::InvalidTwoFactorCode)));
+
+    let (kagi, _profile) = launch(&server, login(PASSWORD, Some(CODE))).await?;
+    let results = kagi
+        .search("rust", 10, &SearchOptions::default(), None)
+        .await?;
+    kagi.close().await?;
+    assert_eq!(results.map(|results| results.len()), Some(5));
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_two_factor_provider() -> anyhow::Result<()> {
+    let asked = Arc::new(AtomicUsize::new(0));
+    let provider = |asked: Arc<AtomicUsize>| {
+        TwoFactorCode::provider(move || {
+            asked.fetch_add(1, Ordering::SeqCst);
+            async { Some(CODE.to_string()) }
+        })
+    };
+    let auth_type = |asked| {
+        AuthType::Login(
+            EMAIL.to_string(),
+            PASSWORD.to_string(),
+            Some(provider(asked)),
+        )
+    };
+
+    // Not asked while Kagi does not require a code
+    let server = MockKagi::start();
+    let (kagi, _profile) = launch(&server, auth_type(asked.clone())).await?;
+    let signed_in = kagi.sign_in(None).await;
+    kagi.close().await?;
+    signed_in?;
+    assert_eq!(asked.load(Ordering::SeqCst), 0);
+
+    let server = MockKagi::with_two_factor();
+    let (kagi, _profile) = launch(&server, auth_type(asked.clone())).await?;
+    let signed_in = kagi.sign_in(None).await;
+    kagi.close().await?;
+    signed_in?;
+    assert_eq!(asked.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_totp() -> anyhow::Result<()> {
+    let server = MockKagi::with_two_factor();
+    let auth_type = AuthType::LoginTotp(
+        EMAIL.to_string(),
+        PASSWORD.to_string(),
+        TOTP_SECRET.to_lowercase(),
+    );
+    let (kagi, _profile) = launch(&server, auth_type).await?;
+
+    let signed_in = kagi.sign_in(None).await;
+    kagi.close().await?;
+
+    signed_in?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_credential_providers() -> anyhow::Result<()> {
+    let server = MockKagi::start();
+
+    let token_file = std::env::temp_dir().join(format!("kagisearch-token-{}", std::process::id()));
+    std::fs::write(&token_file, format!("{}\n", TOKEN))?;
+    let provider = FileCredential::new(&token_file);
+    let (kagi, _profile) = launch(&server, AuthType::TokenFrom(Arc::new(provider))).await?;
+    let signed_in = kagi.sign_in(None).await;
+    kagi.close().await?;
+    std::fs::remove_file(&token_file)?;
+    signed_in?;
+
+    let provider = CommandCredential::new("echo").arg(PASSWORD);
+    let auth_type = AuthType::LoginFrom(EMAIL.to_string(), Arc::new(provider), None);
+    let (kagi, _profile) = launch(&server, auth_type).await?;
+    let signed_in = kagi.sign_in(None).await;
+    kagi.close().await?;
+    signed_in?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_credential_secrets() -> anyhow::Result<()> {
+    let file = std::env::temp_dir().join(format!("kagisearch-secret-{}", std::process::id()));
+    std::fs::write(&file, "secret\r\n")?;
+    let secret = FileCredential::new(&file).secret().await;
+    std::fs::remove_file(&file)?;
+    assert_eq!(secret?.as_str(), "secret");
+
+    let command = CommandCredential::new("printf").arg("secret\\nsecond line");
+    assert_eq!(command.secret().await?.as_str(), "secret");
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_credential_unavailable() {
+    let providers: [Box<dyn CredentialProvider>; 3] = [
+        Box::new(EnvCredential::new("KAGISEARCH_TEST_UNSET")),
+        Box::new(FileCredential::new("/nonexistent/kagi-token")),
+        Box::new(CommandCredential::new("false")),
+    ];
+    for provider in providers {
+        let result = provider.secret().await;
+        assert!(matches!(result, Err(Error::CredentialUnavailable(_))));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn test_invalid_host() {
+    for host in ["kagi.com", "ftp://kagi.com", "https://kagi.com/?q=rust"] {
+        let result = Kagi::builder().host(host).build().await;
+        assert!(
+            matches!(result, Err(Error::InvalidHost(_))),
+            "{} was accepted",
+            host
+        );
+    }
+}
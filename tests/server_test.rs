@@ -0,0 +1,133 @@
+//! Tests of the Kagi Search API server, searching the local mock server. A Chrome or Chromium
+//! installation is required.
+
+mod common;
+
+use std::{path::PathBuf, sync::Arc};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use common::{MockKagi, TOKEN};
+use http_body_util::BodyExt;
+use kagisearch::{AuthType, Kagi, server};
+use serde_json::Value;
+use tower::ServiceExt;
+
+const API_KEY: &str = "local_key";
+
+/// Profile directory of the test browser, deleted when dropped
+struct Profile(PathBuf);
+
+impl Drop for Profile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Serve the API with [`API_KEY`], searching `server`. Keep the returned [`Profile`] until the
+/// browser is closed.
+async fn serve(server: &MockKagi) -> anyhow::Result<(Router, Arc<Kagi>, Profile)> {
+    let profile = Profile(std::env::temp_dir().join(format!(
+        "kagisearch-server-test-{}-{:?}",
+        std::process::id(),
+        std::thread::current().id()
+    )));
+    let kagi = Arc::new(
+        Kagi::builder()
+            .host(server.host())
+            .auth_type(AuthType::Token(TOKEN.to_string()))
+            .user_data_dir(&profile.0)
+            .build()
+            .await?,
+    );
+    let router = server::router(kagi.clone(), Some(API_KEY.to_string()));
+    Ok((router, kagi, profile))
+}
+
+async fn get(
+    router: &Router,
+    uri: &str,
+    authorization: Option<&str>,
+) -> anyhow::Result<(StatusCode, Value)> {
+    let mut request = Request::get(uri);
+    if let Some(authorization) = authorization {
+        request = request.header(AUTHORIZATION, authorization);
+    }
+    let response = router.clone().oneshot(request.body(Body::empty())?).await?;
+    let status = response.status();
+    let body = response.into_body().collect().await?.to_bytes();
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+#[tokio::test]
+async fn test_authorization() -> anyhow::Result<()> {
+    let mock = MockKagi::start();
+    let (router, kagi, _profile) = serve(&mock).await?;
+
+    for authorization in [None, Some("Bot wrong_key"), Some(API_KEY)] {
+        let (status, body) = get(&router, "/api/v0/search?q=rust", authorization).await?;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{:?}", authorization);
+        assert_eq!(body["error"][0]["code"], 401);
+        assert!(body["data"].is_null());
+    }
+    kagi.close().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_query() -> anyhow::Result<()> {
+    let mock = MockKagi::start();
+    let (router, kagi, _profile) = serve(&mock).await?;
+    let authorization = Some(format!("Bot {}", API_KEY));
+
+    for uri in [
+        "/api/v0/search",
+        "/api/v0/search?q=",
+        "/api/v0/search?q=rust&limit=many",
+    ] {
+        let (status, body) = get(&router, uri, authorization.as_deref()).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert_eq!(body["error"][0]["code"], 400);
+        assert!(body["meta"]["id"].is_string());
+        assert!(body["data"].is_null());
+    }
+    kagi.close().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search() -> anyhow::Result<()> {
+    let mock = MockKagi::start();
+    let (router, kagi, _profile) = serve(&mock).await?;
+    let authorization = Some(format!("Bot {}", API_KEY));
+
+    // A limit above the cap is clamped rather than rejected
+    let (status, body) = get(
+        &router,
+        "/api/v0/search?q=rust&limit=1000",
+        authorization.as_deref(),
+    )
+    .await?;
+    kagi.close().await?;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("error").is_none());
+    let data = body["data"].as_array().unwrap();
+    let (results, related): (Vec<_>, Vec<_>) = data.iter().partition(|item| item["t"] == 0);
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0]["rank"], 1);
+    assert_eq!(results[0]["title"], "The Rust Programming Language");
+    assert_eq!(results[0]["url"], "https://www.rust-lang.org/");
+    assert_eq!(results[1]["published"], "3 days ago");
+    assert!(results[0].get("published").is_none());
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0]["t"], 1);
+    assert_eq!(
+        related[0]["list"],
+        serde_json::json!(["rust tutorial", "rust vs go"])
+    );
+    Ok(())
+}